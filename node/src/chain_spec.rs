@@ -1,8 +1,10 @@
 use node_template_runtime::{
-	AccountId, AuraConfig, BalancesConfig, GenesisConfig, GrandpaConfig, Signature, SudoConfig,
-	SystemConfig, WASM_BINARY,
+	AccountId, AuraConfig, Balance, BalancesConfig, BlockNumber, GenesisConfig, GrandpaConfig,
+	KittyModuleConfig, Signature, SudoConfig, SystemConfig, WASM_BINARY,
 };
+use sc_chain_spec::ChainSpecExtension;
 use sc_service::ChainType;
+use serde::{Deserialize, Serialize};
 use sp_consensus_aura::sr25519::AuthorityId as AuraId;
 use sp_core::{sr25519, Pair, Public};
 use sp_finality_grandpa::AuthorityId as GrandpaId;
@@ -11,8 +13,58 @@ use sp_runtime::traits::{IdentifyAccount, Verify};
 // The URL for the telemetry server.
 // const STAGING_TELEMETRY_URL: &str = "wss://telemetry.polkadot.io/submit/";
 
+/// Node settings that all nodes of a network should agree on, kept in the chain spec.
+#[derive(Debug, Default, Clone, Serialize, Deserialize, ChainSpecExtension)]
+#[serde(rename_all = "camelCase")]
+pub struct Extensions {
+	/// GRANDPA parameters.
+	#[serde(default)]
+	pub grandpa: GrandpaSettings,
+}
+
+impl Extensions {
+	/// Try to get the extension from the given `ChainSpec`.
+	pub fn try_get(chain_spec: &dyn sc_service::ChainSpec) -> Option<&Self> {
+		sc_chain_spec::get_extension(chain_spec.extensions())
+	}
+}
+
+/// GRANDPA parameters, each can be overridden on the command line.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct GrandpaSettings {
+	/// How often to gossip, in milliseconds.
+	pub gossip_duration: u64,
+	/// Generate a justification at least every this many blocks.
+	pub justification_period: u32,
+	/// Only vote for blocks at least this many blocks behind the best block.
+	pub before_best_block_by: BlockNumber,
+	/// Only vote up to three quarters of the way from the last finalized to the best block.
+	pub three_quarters_of_unfinalized_chain: bool,
+}
+
+impl GrandpaSettings {
+	/// The settings in `chain_spec`, or the defaults if it has none.
+	pub fn from_chain_spec(chain_spec: &dyn sc_service::ChainSpec) -> Self {
+		Extensions::try_get(chain_spec)
+			.map(|extensions| extensions.grandpa.clone())
+			.unwrap_or_default()
+	}
+}
+
+impl Default for GrandpaSettings {
+	fn default() -> Self {
+		Self {
+			gossip_duration: 333,
+			justification_period: 512,
+			before_best_block_by: 2,
+			three_quarters_of_unfinalized_chain: true,
+		}
+	}
+}
+
 /// Specialized `ChainSpec`. This is a specialization of the general Substrate ChainSpec type.
-pub type ChainSpec = sc_service::GenericChainSpec<GenesisConfig>;
+pub type ChainSpec = sc_service::GenericChainSpec<GenesisConfig, Extensions>;
 
 /// Generate a crypto pair from seed.
 pub fn get_from_seed<TPublic: Public>(seed: &str) -> <TPublic::Pair as Pair>::Public {
@@ -36,6 +88,19 @@ pub fn authority_keys_from_seed(s: &str) -> (AuraId, GrandpaId) {
 	(get_from_seed::<AuraId>(s), get_from_seed::<GrandpaId>(s))
 }
 
+/// Kitties minted at genesis so that frontends start with a populated collection.
+pub fn testnet_kitties() -> Vec<(AccountId, [u8; 16], Option<Balance>)> {
+	let alice = get_account_id_from_seed::<sr25519::Public>("Alice");
+	let bob = get_account_id_from_seed::<sr25519::Public>("Bob");
+
+	vec![
+		(alice.clone(), *b"kitty-alice-0001", None),
+		(alice, *b"kitty-alice-0002", Some(1_000_000)),
+		(bob.clone(), *b"kitty-bob---0001", None),
+		(bob, *b"kitty-bob---0002", Some(2_000_000)),
+	]
+}
+
 pub fn development_config() -> Result<ChainSpec, String> {
 	let wasm_binary = WASM_BINARY.ok_or_else(|| "Development wasm not available".to_string())?;
 
@@ -59,6 +124,8 @@ pub fn development_config() -> Result<ChainSpec, String> {
 					get_account_id_from_seed::<sr25519::Public>("Alice//stash"),
 					get_account_id_from_seed::<sr25519::Public>("Bob//stash"),
 				],
+				// Pre-minted kitties
+				testnet_kitties(),
 				true,
 			)
 		},
@@ -71,7 +138,7 @@ pub fn development_config() -> Result<ChainSpec, String> {
 		// Properties
 		None,
 		// Extensions
-		None,
+		Default::default(),
 	))
 }
 
@@ -106,6 +173,8 @@ pub fn local_testnet_config() -> Result<ChainSpec, String> {
 					get_account_id_from_seed::<sr25519::Public>("Eve//stash"),
 					get_account_id_from_seed::<sr25519::Public>("Ferdie//stash"),
 				],
+				// Pre-minted kitties
+				testnet_kitties(),
 				true,
 			)
 		},
@@ -118,7 +187,7 @@ pub fn local_testnet_config() -> Result<ChainSpec, String> {
 		// Properties
 		None,
 		// Extensions
-		None,
+		Default::default(),
 	))
 }
 
@@ -128,6 +197,7 @@ fn testnet_genesis(
 	initial_authorities: Vec<(AuraId, GrandpaId)>,
 	root_key: AccountId,
 	endowed_accounts: Vec<AccountId>,
+	initial_kitties: Vec<(AccountId, [u8; 16], Option<Balance>)>,
 	_enable_println: bool,
 ) -> GenesisConfig {
 	GenesisConfig {
@@ -150,5 +220,6 @@ fn testnet_genesis(
 			key: root_key,
 		},
 		transaction_payment: Default::default(),
+		kitty_module: KittyModuleConfig { kitties: initial_kitties },
 	}
 }
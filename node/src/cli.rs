@@ -1,3 +1,5 @@
+use crate::{chain_spec::GrandpaSettings, service::Sealing};
+use node_template_runtime::BlockNumber;
 use sc_cli::RunCmd;
 use structopt::StructOpt;
 
@@ -8,6 +10,56 @@ pub struct Cli {
 
 	#[structopt(flatten)]
 	pub run: RunCmd,
+
+	/// Seal blocks with manual seal instead of Aura and GRANDPA, for development:
+	/// `instant`, `manual` (through `engine_createBlock`) or `interval:<ms>`. Only allowed with
+	/// `--dev` or on a chain spec whose chain type is `Development`.
+	#[structopt(long)]
+	pub sealing: Option<Sealing>,
+
+	#[structopt(flatten)]
+	pub grandpa: GrandpaOverrides,
+}
+
+/// Command line overrides of the GRANDPA settings in the chain spec.
+#[derive(Debug, Clone, Default, StructOpt)]
+pub struct GrandpaOverrides {
+	/// GRANDPA gossip duration in milliseconds, instead of the chain spec's `gossipDuration`.
+	#[structopt(long = "grandpa-gossip-duration")]
+	pub gossip_duration: Option<u64>,
+
+	/// GRANDPA justification period, instead of the chain spec's `justificationPeriod`.
+	#[structopt(long = "grandpa-justification-period")]
+	pub justification_period: Option<u32>,
+
+	/// Only vote for blocks this many blocks behind the best block, instead of the chain
+	/// spec's `beforeBestBlockBy`.
+	#[structopt(long = "grandpa-before-best-block-by")]
+	pub before_best_block_by: Option<BlockNumber>,
+
+	/// Whether to only vote up to three quarters of the unfinalized chain (`true` or `false`),
+	/// instead of the chain spec's `threeQuartersOfUnfinalizedChain`.
+	#[structopt(long = "grandpa-three-quarters-of-unfinalized-chain")]
+	pub three_quarters_of_unfinalized_chain: Option<bool>,
+}
+
+impl GrandpaOverrides {
+	/// `settings` with the overrides given on the command line applied.
+	pub fn apply(&self, mut settings: GrandpaSettings) -> GrandpaSettings {
+		if let Some(gossip_duration) = self.gossip_duration {
+			settings.gossip_duration = gossip_duration;
+		}
+		if let Some(justification_period) = self.justification_period {
+			settings.justification_period = justification_period;
+		}
+		if let Some(before_best_block_by) = self.before_best_block_by {
+			settings.before_best_block_by = before_best_block_by;
+		}
+		if let Some(three_quarters) = self.three_quarters_of_unfinalized_chain {
+			settings.three_quarters_of_unfinalized_chain = three_quarters;
+		}
+		settings
+	}
 }
 
 #[derive(Debug, StructOpt)]
@@ -38,4 +90,13 @@ pub enum Subcommand {
 	/// The custom benchmark subcommand benchmarking runtime pallets.
 	#[structopt(name = "benchmark", about = "Benchmark runtime pallets.")]
 	Benchmark(frame_benchmarking_cli::BenchmarkCmd),
+
+	/// Try some command against runtime state. Snapshots taken with `on-runtime-upgrade live
+	/// --snapshot-path <file>` can be replayed offline with `on-runtime-upgrade snap`.
+	#[cfg(feature = "try-runtime")]
+	TryRuntime(try_runtime_cli::TryRuntimeCmd),
+
+	/// Try some command against runtime state. Note: `try-runtime` feature must be enabled.
+	#[cfg(not(feature = "try-runtime"))]
+	TryRuntime,
 }
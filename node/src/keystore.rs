@@ -0,0 +1,824 @@
+//! A keystore that keeps the keys in a separate signer process.
+//!
+//! The node talks to the signer with JSON-RPC 2.0, one request per connection, either over
+//! HTTP (`--keystore-uri http://127.0.0.1:9955`) or over a Unix socket
+//! (`--keystore-uri unix:///run/signer.sock`). On a Unix socket the request and the response
+//! are each a single line of JSON; over HTTP the signer must answer with a `Content-Length`
+//! or close the connection after the body.
+//!
+//! Plain HTTP is neither encrypted nor authenticated, so it is only accepted to a loopback
+//! address (`127.0.0.0/8`, `[::1]` or `localhost`). A signer on another machine has to be
+//! exposed through a Unix socket, e.g. forwarded over SSH.
+//!
+//! Key types and crypto types are their four-character names (`"aura"`, `"gran"`; `"sr25"`,
+//! `"ed25"`, `"ecds"`), keys, messages and signatures are `0x`-prefixed hex:
+//!
+//! | method                   | params                                | result              |
+//! |--------------------------|---------------------------------------|---------------------|
+//! | `keystore_publicKeys`    | `[key_type, crypto]`                  | `[public]`          |
+//! | `keystore_generateNew`   | `[key_type, crypto, seed or null]`    | `public`            |
+//! | `keystore_insertUnknown` | `[key_type, suri, public]`            | `null`              |
+//! | `keystore_hasKeys`       | `[[[public, key_type], ...]]`         | `bool`              |
+//! | `keystore_sign`          | `[key_type, crypto, public, message]` | `signature or null` |
+//! | `keystore_signPrehashed` | `[key_type, public, hash]`            | `signature or null` |
+//!
+//! A `null` signature means the signer doesn't hold the key. VRF signing is not part of the
+//! protocol, this node only runs Aura and GRANDPA.
+
+use async_trait::async_trait;
+use serde::{de::DeserializeOwned, Deserialize};
+use serde_json::{json, Value};
+use sp_core::{
+	crypto::{CryptoTypeId, CryptoTypePublicPair, KeyTypeId},
+	ecdsa, ed25519, sr25519, Bytes,
+};
+use sp_keystore::{
+	vrf::{VRFSignature, VRFTranscriptData},
+	CryptoStore, Error, SyncCryptoStore,
+};
+use std::{
+	convert::TryFrom,
+	io::{self, BufRead, BufReader, Read, Write},
+	net::{IpAddr, TcpStream},
+	os::unix::net::UnixStream,
+	path::PathBuf,
+	sync::atomic::{AtomicU64, Ordering},
+	time::Duration,
+};
+
+/// How long to wait for the signer before giving up on a request.
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Where the signer listens.
+#[derive(Debug, Clone, PartialEq)]
+enum Endpoint {
+	/// `host:port` and the request path.
+	Http {
+		host: String,
+		path: String,
+	},
+	Unix(PathBuf),
+}
+
+impl Endpoint {
+	fn parse(url: &str) -> Result<Self, String> {
+		if let Some(path) = url.strip_prefix("unix://") {
+			if path.is_empty() {
+				return Err(format!("missing socket path in {}", url))
+			}
+			return Ok(Endpoint::Unix(path.into()))
+		}
+
+		if let Some(rest) = url.strip_prefix("http://") {
+			let (host, path) = match rest.find('/') {
+				Some(i) => (&rest[..i], &rest[i..]),
+				None => (rest, "/"),
+			};
+			if host.is_empty() {
+				return Err(format!("missing host in {}", url))
+			}
+			let (name, has_port) = match host.strip_prefix('[') {
+				Some(rest) => match rest.split_once(']') {
+					Some((name, port)) => (name, !port.is_empty()),
+					None => return Err(format!("invalid host in {}", url)),
+				},
+				None => match host.split_once(':') {
+					Some((name, _)) => (name, true),
+					None => (host, false),
+				},
+			};
+			let loopback = name.eq_ignore_ascii_case("localhost") ||
+				name.parse::<IpAddr>().map_or(false, |ip| ip.is_loopback());
+			if !loopback {
+				return Err(format!(
+					"refusing unencrypted http:// to {}, use a loopback address or a unix:// socket",
+					name
+				))
+			}
+			let host = if has_port { host.to_string() } else { format!("{}:80", host) };
+			return Ok(Endpoint::Http { host, path: path.to_string() })
+		}
+
+		Err(format!("unsupported keystore uri {}, expected http:// or unix://", url))
+	}
+
+	fn request(&self, body: &[u8]) -> io::Result<Vec<u8>> {
+		match self {
+			Endpoint::Http { host, path } => http_post(host, path, body),
+			Endpoint::Unix(path) => {
+				let mut stream = UnixStream::connect(path)?;
+				stream.set_read_timeout(Some(TIMEOUT))?;
+				stream.set_write_timeout(Some(TIMEOUT))?;
+				stream.write_all(body)?;
+				stream.write_all(b"\n")?;
+
+				let mut line = Vec::new();
+				BufReader::new(stream).read_until(b'\n', &mut line)?;
+				Ok(line)
+			},
+		}
+	}
+}
+
+fn http_post(host: &str, path: &str, body: &[u8]) -> io::Result<Vec<u8>> {
+	let mut stream = TcpStream::connect(host)?;
+	stream.set_read_timeout(Some(TIMEOUT))?;
+	stream.set_write_timeout(Some(TIMEOUT))?;
+	write!(
+		stream,
+		"POST {} HTTP/1.1\r\nHost: {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+		path,
+		host,
+		body.len()
+	)?;
+	stream.write_all(body)?;
+
+	let mut reader = BufReader::new(stream);
+	let mut status = String::new();
+	reader.read_line(&mut status)?;
+	if status.split_whitespace().nth(1) != Some("200") {
+		return Err(io::Error::new(
+			io::ErrorKind::Other,
+			format!("unexpected HTTP status: {}", status.trim()),
+		));
+	}
+
+	let mut content_length = None;
+	loop {
+		let mut line = String::new();
+		reader.read_line(&mut line)?;
+		let line = line.trim_end();
+		if line.is_empty() {
+			break
+		}
+		if let Some((name, value)) = line.split_once(':') {
+			if name.eq_ignore_ascii_case("content-length") {
+				content_length = value.trim().parse::<usize>().ok();
+			}
+		}
+	}
+
+	let mut response = Vec::new();
+	match content_length {
+		Some(len) => {
+			response.resize(len, 0);
+			reader.read_exact(&mut response)?;
+		},
+		None => {
+			reader.read_to_end(&mut response)?;
+		},
+	}
+	Ok(response)
+}
+
+#[derive(Deserialize)]
+struct Response {
+	#[serde(default)]
+	result: Option<Value>,
+	#[serde(default)]
+	error: Option<ResponseError>,
+}
+
+#[derive(Deserialize)]
+struct ResponseError {
+	message: String,
+}
+
+fn key_type(id: KeyTypeId) -> String {
+	String::from_utf8_lossy(&id.0).into_owned()
+}
+
+fn crypto_type(id: CryptoTypeId) -> String {
+	String::from_utf8_lossy(&id.0).into_owned()
+}
+
+fn decode_public<P: for<'a> TryFrom<&'a [u8]>>(public: Bytes) -> Result<P, Error> {
+	P::try_from(&public.0[..])
+		.map_err(|_| Error::ValidationError("signer returned a malformed public key".into()))
+}
+
+/// A `CryptoStore` whose keys live in a signer reached over JSON-RPC.
+///
+/// Every call is a blocking round trip to the signer, like `LocalKeystore` the async methods
+/// just run the sync ones.
+pub struct RemoteKeystore {
+	endpoint: Endpoint,
+	next_id: AtomicU64,
+}
+
+impl RemoteKeystore {
+	/// Connects to the signer at `url` and checks that it answers.
+	pub fn open(url: &str) -> Result<Self, String> {
+		let keystore = Self { endpoint: Endpoint::parse(url)?, next_id: AtomicU64::new(1) };
+		keystore
+			.call::<bool>("keystore_hasKeys", json!([[]]))
+			.map_err(|e| format!("signer is not answering: {}", e))?;
+		Ok(keystore)
+	}
+
+	fn call<R: DeserializeOwned>(&self, method: &str, params: Value) -> Result<R, Error> {
+		let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+		let request = json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params });
+		let body = serde_json::to_vec(&request).expect("a JSON value always serializes; qed");
+
+		let response = self.endpoint.request(&body).map_err(|e| {
+			log::warn!(target: "keystore", "Remote keystore request `{}` failed: {}", method, e);
+			Error::Unavailable
+		})?;
+		let response: Response = serde_json::from_slice(&response)
+			.map_err(|e| Error::Other(format!("invalid response to `{}`: {}", method, e)))?;
+		if let Some(error) = response.error {
+			return Err(Error::Other(format!("`{}` failed: {}", method, error.message)))
+		}
+
+		serde_json::from_value(response.result.unwrap_or(Value::Null))
+			.map_err(|e| Error::Other(format!("invalid result of `{}`: {}", method, e)))
+	}
+
+	fn public_keys<P: for<'a> TryFrom<&'a [u8]>>(
+		&self,
+		id: KeyTypeId,
+		crypto: CryptoTypeId,
+	) -> Vec<P> {
+		match self
+			.call::<Vec<Bytes>>("keystore_publicKeys", json!([key_type(id), crypto_type(crypto)]))
+		{
+			Ok(keys) => keys.into_iter().filter_map(|k| decode_public(k).ok()).collect(),
+			Err(e) => {
+				log::warn!(
+					target: "keystore",
+					"Failed to list remote {} keys: {}",
+					key_type(id),
+					e
+				);
+				Vec::new()
+			},
+		}
+	}
+
+	fn generate_new<P: for<'a> TryFrom<&'a [u8]>>(
+		&self,
+		id: KeyTypeId,
+		crypto: CryptoTypeId,
+		seed: Option<&str>,
+	) -> Result<P, Error> {
+		let public = self.call::<Bytes>(
+			"keystore_generateNew",
+			json!([key_type(id), crypto_type(crypto), seed]),
+		)?;
+		decode_public(public)
+	}
+}
+
+impl SyncCryptoStore for RemoteKeystore {
+	fn sr25519_public_keys(&self, id: KeyTypeId) -> Vec<sr25519::Public> {
+		self.public_keys(id, sr25519::CRYPTO_ID)
+	}
+
+	fn sr25519_generate_new(
+		&self,
+		id: KeyTypeId,
+		seed: Option<&str>,
+	) -> Result<sr25519::Public, Error> {
+		self.generate_new(id, sr25519::CRYPTO_ID, seed)
+	}
+
+	fn ed25519_public_keys(&self, id: KeyTypeId) -> Vec<ed25519::Public> {
+		self.public_keys(id, ed25519::CRYPTO_ID)
+	}
+
+	fn ed25519_generate_new(
+		&self,
+		id: KeyTypeId,
+		seed: Option<&str>,
+	) -> Result<ed25519::Public, Error> {
+		self.generate_new(id, ed25519::CRYPTO_ID, seed)
+	}
+
+	fn ecdsa_public_keys(&self, id: KeyTypeId) -> Vec<ecdsa::Public> {
+		self.public_keys(id, ecdsa::CRYPTO_ID)
+	}
+
+	fn ecdsa_generate_new(
+		&self,
+		id: KeyTypeId,
+		seed: Option<&str>,
+	) -> Result<ecdsa::Public, Error> {
+		self.generate_new(id, ecdsa::CRYPTO_ID, seed)
+	}
+
+	fn insert_unknown(&self, id: KeyTypeId, suri: &str, public: &[u8]) -> Result<(), ()> {
+		self.call::<Value>(
+			"keystore_insertUnknown",
+			json!([key_type(id), suri, Bytes(public.to_vec())]),
+		)
+		.map(|_| ())
+		.map_err(|e| {
+			log::warn!(target: "keystore", "Failed to insert remote {} key: {}", key_type(id), e);
+		})
+	}
+
+	fn supported_keys(
+		&self,
+		id: KeyTypeId,
+		keys: Vec<CryptoTypePublicPair>,
+	) -> Result<Vec<CryptoTypePublicPair>, Error> {
+		let available = SyncCryptoStore::keys(self, id)?;
+		Ok(keys.into_iter().filter(|k| available.contains(k)).collect())
+	}
+
+	fn keys(&self, id: KeyTypeId) -> Result<Vec<CryptoTypePublicPair>, Error> {
+		let mut keys = Vec::new();
+		for crypto in [sr25519::CRYPTO_ID, ed25519::CRYPTO_ID, ecdsa::CRYPTO_ID] {
+			let public = self.call::<Vec<Bytes>>(
+				"keystore_publicKeys",
+				json!([key_type(id), crypto_type(crypto)]),
+			)?;
+			keys.extend(public.into_iter().map(|p| CryptoTypePublicPair(crypto, p.0)));
+		}
+		Ok(keys)
+	}
+
+	fn has_keys(&self, public_keys: &[(Vec<u8>, KeyTypeId)]) -> bool {
+		let keys = public_keys
+			.iter()
+			.map(|(public, id)| json!([Bytes(public.clone()), key_type(*id)]))
+			.collect::<Vec<_>>();
+		self.call("keystore_hasKeys", json!([keys])).unwrap_or(false)
+	}
+
+	fn sign_with(
+		&self,
+		id: KeyTypeId,
+		key: &CryptoTypePublicPair,
+		msg: &[u8],
+	) -> Result<Option<Vec<u8>>, Error> {
+		if ![sr25519::CRYPTO_ID, ed25519::CRYPTO_ID, ecdsa::CRYPTO_ID].contains(&key.0) {
+			return Err(Error::KeyNotSupported(id))
+		}
+
+		let signature = self.call::<Option<Bytes>>(
+			"keystore_sign",
+			json!([key_type(id), crypto_type(key.0), Bytes(key.1.clone()), Bytes(msg.to_vec())]),
+		)?;
+		Ok(signature.map(|s| s.0))
+	}
+
+	fn sr25519_vrf_sign(
+		&self,
+		_key_type: KeyTypeId,
+		_public: &sr25519::Public,
+		_transcript_data: VRFTranscriptData,
+	) -> Result<Option<VRFSignature>, Error> {
+		Err(Error::Other("VRF signing is not supported by the remote keystore".into()))
+	}
+
+	fn ecdsa_sign_prehashed(
+		&self,
+		id: KeyTypeId,
+		public: &ecdsa::Public,
+		msg: &[u8; 32],
+	) -> Result<Option<ecdsa::Signature>, Error> {
+		let signature = self.call::<Option<Bytes>>(
+			"keystore_signPrehashed",
+			json!([key_type(id), Bytes(public.0.to_vec()), Bytes(msg.to_vec())]),
+		)?;
+		signature
+			.map(|s| {
+				ecdsa::Signature::try_from(&s.0[..]).map_err(|_| {
+					Error::ValidationError("signer returned a malformed signature".into())
+				})
+			})
+			.transpose()
+	}
+}
+
+#[async_trait]
+impl CryptoStore for RemoteKeystore {
+	async fn sr25519_public_keys(&self, id: KeyTypeId) -> Vec<sr25519::Public> {
+		SyncCryptoStore::sr25519_public_keys(self, id)
+	}
+
+	async fn sr25519_generate_new(
+		&self,
+		id: KeyTypeId,
+		seed: Option<&str>,
+	) -> Result<sr25519::Public, Error> {
+		SyncCryptoStore::sr25519_generate_new(self, id, seed)
+	}
+
+	async fn ed25519_public_keys(&self, id: KeyTypeId) -> Vec<ed25519::Public> {
+		SyncCryptoStore::ed25519_public_keys(self, id)
+	}
+
+	async fn ed25519_generate_new(
+		&self,
+		id: KeyTypeId,
+		seed: Option<&str>,
+	) -> Result<ed25519::Public, Error> {
+		SyncCryptoStore::ed25519_generate_new(self, id, seed)
+	}
+
+	async fn ecdsa_public_keys(&self, id: KeyTypeId) -> Vec<ecdsa::Public> {
+		SyncCryptoStore::ecdsa_public_keys(self, id)
+	}
+
+	async fn ecdsa_generate_new(
+		&self,
+		id: KeyTypeId,
+		seed: Option<&str>,
+	) -> Result<ecdsa::Public, Error> {
+		SyncCryptoStore::ecdsa_generate_new(self, id, seed)
+	}
+
+	async fn insert_unknown(&self, id: KeyTypeId, suri: &str, public: &[u8]) -> Result<(), ()> {
+		SyncCryptoStore::insert_unknown(self, id, suri, public)
+	}
+
+	async fn supported_keys(
+		&self,
+		id: KeyTypeId,
+		keys: Vec<CryptoTypePublicPair>,
+	) -> Result<Vec<CryptoTypePublicPair>, Error> {
+		SyncCryptoStore::supported_keys(self, id, keys)
+	}
+
+	async fn keys(&self, id: KeyTypeId) -> Result<Vec<CryptoTypePublicPair>, Error> {
+		SyncCryptoStore::keys(self, id)
+	}
+
+	async fn has_keys(&self, public_keys: &[(Vec<u8>, KeyTypeId)]) -> bool {
+		SyncCryptoStore::has_keys(self, public_keys)
+	}
+
+	async fn sign_with(
+		&self,
+		id: KeyTypeId,
+		key: &CryptoTypePublicPair,
+		msg: &[u8],
+	) -> Result<Option<Vec<u8>>, Error> {
+		SyncCryptoStore::sign_with(self, id, key, msg)
+	}
+
+	async fn sr25519_vrf_sign(
+		&self,
+		key_type: KeyTypeId,
+		public: &sr25519::Public,
+		transcript_data: VRFTranscriptData,
+	) -> Result<Option<VRFSignature>, Error> {
+		SyncCryptoStore::sr25519_vrf_sign(self, key_type, public, transcript_data)
+	}
+
+	async fn ecdsa_sign_prehashed(
+		&self,
+		id: KeyTypeId,
+		public: &ecdsa::Public,
+		msg: &[u8; 32],
+	) -> Result<Option<ecdsa::Signature>, Error> {
+		SyncCryptoStore::ecdsa_sign_prehashed(self, id, public, msg)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{Endpoint, RemoteKeystore};
+	use serde_json::{json, Value};
+	use sp_core::{
+		crypto::{
+			key_types::{AURA, GRANDPA},
+			CryptoTypeId, CryptoTypePublicPair, KeyTypeId,
+		},
+		ecdsa, ed25519,
+		hashing::blake2_256,
+		sr25519, Bytes, Pair,
+	};
+	use sp_keystore::{testing::KeyStore, Error, SyncCryptoStore};
+	use std::{
+		io::{BufRead, BufReader, Read, Write},
+		net::{TcpListener, TcpStream},
+		os::unix::net::{UnixListener, UnixStream},
+		process::{Child, Command, Stdio},
+	};
+
+	/// Tells a re-executed test binary to run the stand-in signer at this uri.
+	const SIGNER_ENV: &str = "KITTIES_STAND_IN_SIGNER";
+	/// Printed by the stand-in signer once it accepts connections, followed by its uri.
+	const LISTENING: &str = "stand-in signer listening on ";
+
+	fn crypto_id(value: &Value) -> CryptoTypeId {
+		match value.as_str().unwrap() {
+			"sr25" => sr25519::CRYPTO_ID,
+			"ed25" => ed25519::CRYPTO_ID,
+			"ecds" => ecdsa::CRYPTO_ID,
+			other => panic!("unknown crypto type {}", other),
+		}
+	}
+
+	fn bytes(value: &Value) -> Vec<u8> {
+		serde_json::from_value::<Bytes>(value.clone()).unwrap().0
+	}
+
+	/// The stand-in signer: answers one request from an in-memory keystore.
+	fn respond(keystore: &KeyStore, request: &[u8]) -> Vec<u8> {
+		let request: Value = serde_json::from_slice(request).unwrap();
+		let params = &request["params"];
+		let key_type = |i: usize| KeyTypeId::try_from(params[i].as_str().unwrap()).unwrap();
+		let error = |code: i64, message: String| {
+			let error = json!({
+				"jsonrpc": "2.0",
+				"id": request["id"],
+				"error": { "code": code, "message": message },
+			});
+			serde_json::to_vec(&error).unwrap()
+		};
+
+		let result = match request["method"].as_str().unwrap() {
+			"keystore_publicKeys" => {
+				let crypto = crypto_id(&params[1]);
+				let keys = keystore.keys(key_type(0)).unwrap();
+				json!(keys
+					.into_iter()
+					.filter(|k| k.0 == crypto)
+					.map(|k| Bytes(k.1))
+					.collect::<Vec<_>>())
+			},
+			"keystore_generateNew" => {
+				let (id, seed) = (key_type(0), params[2].as_str());
+				let public = match crypto_id(&params[1]) {
+					sr25519::CRYPTO_ID =>
+						keystore.sr25519_generate_new(id, seed).unwrap().0.to_vec(),
+					ed25519::CRYPTO_ID =>
+						keystore.ed25519_generate_new(id, seed).unwrap().0.to_vec(),
+					_ => keystore.ecdsa_generate_new(id, seed).unwrap().0.to_vec(),
+				};
+				json!(Bytes(public))
+			},
+			"keystore_insertUnknown" => {
+				let suri = params[1].as_str().unwrap();
+				if sr25519::Pair::from_string(suri, None).is_err() {
+					return error(-32602, format!("invalid suri {}", suri))
+				}
+				keystore.insert_unknown(key_type(0), suri, &bytes(&params[2])).unwrap();
+				Value::Null
+			},
+			"keystore_hasKeys" => {
+				let keys = params[0]
+					.as_array()
+					.unwrap()
+					.iter()
+					.map(|k| (bytes(&k[0]), KeyTypeId::try_from(k[1].as_str().unwrap()).unwrap()))
+					.collect::<Vec<_>>();
+				json!(keystore.has_keys(&keys))
+			},
+			"keystore_sign" => {
+				let key = CryptoTypePublicPair(crypto_id(&params[1]), bytes(&params[2]));
+				let signature = keystore.sign_with(key_type(0), &key, &bytes(&params[3])).unwrap();
+				json!(signature.map(Bytes))
+			},
+			"keystore_signPrehashed" => {
+				let public = ecdsa::Public::try_from(&bytes(&params[1])[..]).unwrap();
+				let hash: [u8; 32] = bytes(&params[2]).try_into().unwrap();
+				let signature = keystore.ecdsa_sign_prehashed(key_type(0), &public, &hash).unwrap();
+				json!(signature.map(|s| Bytes(s.0.to_vec())))
+			},
+			method => return error(-32601, format!("unknown method {}", method)),
+		};
+
+		serde_json::to_vec(&json!({ "jsonrpc": "2.0", "id": request["id"], "result": result }))
+			.unwrap()
+	}
+
+	fn serve_unix(keystore: &KeyStore, stream: UnixStream) {
+		let mut line = Vec::new();
+		BufReader::new(&stream).read_until(b'\n', &mut line).unwrap();
+		let mut response = respond(keystore, &line);
+		response.push(b'\n');
+		(&stream).write_all(&response).unwrap();
+	}
+
+	fn serve_http(keystore: &KeyStore, stream: TcpStream) {
+		let mut reader = BufReader::new(&stream);
+		let mut content_length = 0;
+		loop {
+			let mut line = String::new();
+			reader.read_line(&mut line).unwrap();
+			let line = line.trim_end();
+			if line.is_empty() {
+				break
+			}
+			if let Some(len) = line.to_ascii_lowercase().strip_prefix("content-length:") {
+				content_length = len.trim().parse().unwrap();
+			}
+		}
+		let mut body = vec![0; content_length];
+		reader.read_exact(&mut body).unwrap();
+
+		let response = respond(keystore, &body);
+		write!(
+			&stream,
+			"HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n",
+			response.len()
+		)
+		.unwrap();
+		(&stream).write_all(&response).unwrap();
+	}
+
+	/// Entry point of the stand-in signer process, see `Signer::spawn`. Does nothing when the
+	/// ignored tests are run by hand.
+	#[test]
+	#[ignore]
+	fn stand_in_signer() {
+		let uri = match std::env::var(SIGNER_ENV) {
+			Ok(uri) => uri,
+			Err(_) => return,
+		};
+
+		let keystore = KeyStore::new();
+		match Endpoint::parse(&uri).unwrap() {
+			Endpoint::Unix(path) => {
+				let listener = UnixListener::bind(&path).unwrap();
+				println!("{}{}", LISTENING, uri);
+				for stream in listener.incoming() {
+					serve_unix(&keystore, stream.unwrap());
+				}
+			},
+			Endpoint::Http { host, .. } => {
+				let listener = TcpListener::bind(&host).unwrap();
+				println!("{}http://{}", LISTENING, listener.local_addr().unwrap());
+				for stream in listener.incoming() {
+					serve_http(&keystore, stream.unwrap());
+				}
+			},
+		}
+	}
+
+	/// The stand-in signer, running in a separate process that is killed on drop.
+	struct Signer {
+		process: Child,
+		uri: String,
+	}
+
+	impl Signer {
+		/// Re-executes this test binary to run `stand_in_signer` at `uri`, and waits until it
+		/// accepts connections.
+		fn spawn(uri: &str) -> Self {
+			let mut process = Command::new(std::env::current_exe().unwrap())
+				.args(&["keystore::tests::stand_in_signer", "--exact", "--ignored", "--nocapture"])
+				.env(SIGNER_ENV, uri)
+				.stdout(Stdio::piped())
+				.spawn()
+				.unwrap();
+
+			let stdout = BufReader::new(process.stdout.take().unwrap());
+			let uri = stdout
+				.lines()
+				.find_map(|line| line.unwrap().strip_prefix(LISTENING).map(str::to_string))
+				.expect("the stand-in signer exited before listening");
+			Signer { process, uri }
+		}
+
+		fn unix(name: &str) -> Self {
+			let path = std::env::temp_dir().join(format!(
+				"kitties-signer-{}-{}.sock",
+				std::process::id(),
+				name
+			));
+			let _ = std::fs::remove_file(&path);
+			Self::spawn(&format!("unix://{}", path.display()))
+		}
+
+		fn http() -> Self {
+			Self::spawn("http://127.0.0.1:0")
+		}
+	}
+
+	impl Drop for Signer {
+		fn drop(&mut self) {
+			let _ = self.process.kill();
+			let _ = self.process.wait();
+			if let Ok(Endpoint::Unix(path)) = Endpoint::parse(&self.uri) {
+				let _ = std::fs::remove_file(path);
+			}
+		}
+	}
+
+	#[test]
+	fn parses_keystore_uris() {
+		assert_eq!(
+			Endpoint::parse("http://127.0.0.1:9955"),
+			Ok(Endpoint::Http { host: "127.0.0.1:9955".into(), path: "/".into() })
+		);
+		assert_eq!(
+			Endpoint::parse("http://localhost/rpc"),
+			Ok(Endpoint::Http { host: "localhost:80".into(), path: "/rpc".into() })
+		);
+		assert_eq!(
+			Endpoint::parse("http://[::1]/rpc"),
+			Ok(Endpoint::Http { host: "[::1]:80".into(), path: "/rpc".into() })
+		);
+		assert_eq!(
+			Endpoint::parse("unix:///run/signer.sock"),
+			Ok(Endpoint::Unix("/run/signer.sock".into()))
+		);
+		assert!(Endpoint::parse("https://signer").is_err());
+		assert!(Endpoint::parse("unix://").is_err());
+		assert!(Endpoint::parse("http:///rpc").is_err());
+	}
+
+	#[test]
+	fn refuses_http_to_other_hosts() {
+		assert!(Endpoint::parse("http://signer:9955").is_err());
+		assert!(Endpoint::parse("http://10.0.0.2:9955").is_err());
+		assert!(Endpoint::parse("http://[2001:db8::1]:9955").is_err());
+		assert!(Endpoint::parse("http://[::1").is_err());
+		assert!(RemoteKeystore::open("http://192.0.2.1:9955").is_err());
+	}
+
+	#[test]
+	fn signs_through_unix_socket() {
+		let signer = Signer::unix("sign");
+		let remote = RemoteKeystore::open(&signer.uri).unwrap();
+
+		let public = remote.sr25519_generate_new(AURA, None).unwrap();
+		assert_eq!(remote.sr25519_public_keys(AURA), vec![public]);
+		assert!(remote.sr25519_public_keys(GRANDPA).is_empty());
+		assert!(remote.has_keys(&[(public.0.to_vec(), AURA)]));
+		assert!(!remote.has_keys(&[(public.0.to_vec(), GRANDPA)]));
+
+		let key = CryptoTypePublicPair(sr25519::CRYPTO_ID, public.0.to_vec());
+		let signature = remote.sign_with(AURA, &key, b"kitty").unwrap().unwrap();
+		let signature = sr25519::Signature::try_from(&signature[..]).unwrap();
+		assert!(sr25519::Pair::verify(&signature, b"kitty", &public));
+
+		// A key the signer doesn't hold.
+		let unknown = CryptoTypePublicPair(sr25519::CRYPTO_ID, vec![0; 32]);
+		assert_eq!(remote.sign_with(AURA, &unknown, b"kitty").unwrap(), None);
+	}
+
+	#[test]
+	fn signs_over_http() {
+		let signer = Signer::http();
+		let remote = RemoteKeystore::open(&signer.uri).unwrap();
+
+		let public = remote.ed25519_generate_new(GRANDPA, Some("//Alice")).unwrap();
+		assert_eq!(public, ed25519::Pair::from_string("//Alice", None).unwrap().public());
+		assert_eq!(
+			remote.keys(GRANDPA).unwrap(),
+			vec![CryptoTypePublicPair(ed25519::CRYPTO_ID, public.0.to_vec())]
+		);
+
+		let key = CryptoTypePublicPair(ed25519::CRYPTO_ID, public.0.to_vec());
+		let signature = remote.sign_with(GRANDPA, &key, b"finality").unwrap().unwrap();
+		let signature = ed25519::Signature::try_from(&signature[..]).unwrap();
+		assert!(ed25519::Pair::verify(&signature, b"finality", &public));
+	}
+
+	#[test]
+	fn inserts_known_keys() {
+		let signer = Signer::unix("insert");
+		let remote = RemoteKeystore::open(&signer.uri).unwrap();
+
+		let alice = sr25519::Pair::from_string("//Alice", None).unwrap().public();
+		assert_eq!(remote.insert_unknown(AURA, "//Alice", alice.as_ref()), Ok(()));
+		assert_eq!(remote.sr25519_public_keys(AURA), vec![alice]);
+
+		let key = CryptoTypePublicPair(sr25519::CRYPTO_ID, alice.0.to_vec());
+		let signature = remote.sign_with(AURA, &key, b"kitty").unwrap().unwrap();
+		let signature = sr25519::Signature::try_from(&signature[..]).unwrap();
+		assert!(sr25519::Pair::verify(&signature, b"kitty", &alice));
+	}
+
+	#[test]
+	fn signs_prehashed_messages() {
+		let signer = Signer::unix("prehashed");
+		let remote = RemoteKeystore::open(&signer.uri).unwrap();
+		let key_type = KeyTypeId(*b"test");
+
+		let public = remote.ecdsa_generate_new(key_type, None).unwrap();
+		let hash = blake2_256(b"kitty");
+		let signature = remote.ecdsa_sign_prehashed(key_type, &public, &hash).unwrap().unwrap();
+		assert!(ecdsa::Pair::verify_prehashed(&signature, &hash, &public));
+
+		// A key the signer doesn't hold.
+		let unknown = ecdsa::Public::from_raw([0; 33]);
+		assert_eq!(remote.ecdsa_sign_prehashed(key_type, &unknown, &hash).unwrap(), None);
+	}
+
+	#[test]
+	fn reports_signer_failures() {
+		let path = std::env::temp_dir().join("kitties-signer-missing.sock");
+		assert!(RemoteKeystore::open(&format!("unix://{}", path.display())).is_err());
+
+		let signer = Signer::unix("errors");
+		let remote = RemoteKeystore::open(&signer.uri).unwrap();
+		assert!(matches!(remote.insert_unknown(AURA, "not a suri", &[0; 32]), Err(())));
+		assert!(matches!(
+			remote.call::<Value>("keystore_unknown", json!([])),
+			Err(Error::Other(message)) if message.contains("unknown method")
+		));
+		assert!(matches!(
+			remote.sign_with(AURA, &CryptoTypePublicPair(CryptoTypeId(*b"abcd"), vec![]), b""),
+			Err(Error::KeyNotSupported(_))
+		));
+	}
+}
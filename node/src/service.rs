@@ -1,16 +1,23 @@
 //! Service and ServiceFactory implementation. Specialized wrapper over substrate service.
 
-use node_template_runtime::{self, opaque::Block, RuntimeApi};
+use crate::{chain_spec::GrandpaSettings, keystore::RemoteKeystore};
+use futures::{channel::mpsc, stream, Stream, StreamExt};
+use futures_timer::Delay;
+use node_template_runtime::{self, opaque::Block, Hash, RuntimeApi};
 use sc_client_api::ExecutorProvider;
 use sc_consensus_aura::{ImportQueueParams, SlotProportion, StartAuraParams};
+use sc_consensus_manual_seal::{
+	consensus::{aura::AuraConsensusDataProvider, timestamp::SlotTimestampProvider},
+	EngineCommand, ManualSealParams,
+};
 pub use sc_executor::NativeElseWasmExecutor;
 use sc_finality_grandpa::SharedVoterState;
-use sc_keystore::LocalKeystore;
 use sc_service::{error::Error as ServiceError, Configuration, TaskManager};
 use sc_telemetry::{Telemetry, TelemetryWorker};
+use sc_transaction_pool_api::TransactionPool;
 use sp_consensus::SlotData;
 use sp_consensus_aura::sr25519::AuthorityPair as AuraPair;
-use std::{sync::Arc, time::Duration};
+use std::{pin::Pin, str::FromStr, sync::Arc, time::Duration};
 
 // Our native executor instance.
 pub struct ExecutorDispatch;
@@ -36,16 +43,78 @@ type FullClient =
 	sc_service::TFullClient<Block, RuntimeApi, NativeElseWasmExecutor<ExecutorDispatch>>;
 type FullBackend = sc_service::TFullBackend<Block>;
 type FullSelectChain = sc_consensus::LongestChain<FullBackend, Block>;
+type FullPool = sc_transaction_pool::FullPool<Block, FullClient>;
+
+/// How blocks are sealed when the node runs without Aura and GRANDPA, for development.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Sealing {
+	/// Seal a block as soon as a transaction enters the pool.
+	Instant,
+	/// Seal blocks only when asked through `engine_createBlock`.
+	Manual,
+	/// Seal a block every given number of milliseconds.
+	Interval(u64),
+}
+
+impl FromStr for Sealing {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		Ok(match s {
+			"instant" => Self::Instant,
+			"manual" => Self::Manual,
+			s => match s.strip_prefix("interval:").map(str::parse) {
+				Some(Ok(millis)) if millis > 0 => Self::Interval(millis),
+				_ =>
+					return Err(format!(
+						"invalid sealing {}, expected instant, manual or interval:<ms>",
+						s
+					)),
+			},
+		})
+	}
+}
+
+type SealCommands = Pin<Box<dyn Stream<Item = EngineCommand<Hash>> + Send>>;
+
+fn seal_new_block(create_empty: bool) -> EngineCommand<Hash> {
+	// Nothing else finalizes blocks without GRANDPA, so the automatic modes do it themselves.
+	EngineCommand::SealNewBlock { create_empty, finalize: true, parent_hash: None, sender: None }
+}
+
+/// The commands driving manual seal, and for `Sealing::Manual` the sink the `engine_*` RPC
+/// methods send them to.
+fn seal_commands(
+	sealing: Sealing,
+	pool: &FullPool,
+) -> (SealCommands, Option<mpsc::Sender<EngineCommand<Hash>>>) {
+	match sealing {
+		Sealing::Instant =>
+			(Box::pin(pool.import_notification_stream().map(|_| seal_new_block(false))), None),
+		Sealing::Manual => {
+			let (sink, commands) = mpsc::channel(1024);
+			(Box::pin(commands), Some(sink))
+		},
+		Sealing::Interval(millis) => {
+			let commands = stream::unfold((), move |()| async move {
+				Delay::new(Duration::from_millis(millis)).await;
+				Some((seal_new_block(true), ()))
+			});
+			(Box::pin(commands), None)
+		},
+	}
+}
 
 pub fn new_partial(
 	config: &Configuration,
+	sealing: Option<Sealing>,
 ) -> Result<
 	sc_service::PartialComponents<
 		FullClient,
 		FullBackend,
 		FullSelectChain,
 		sc_consensus::DefaultImportQueue<Block, FullClient>,
-		sc_transaction_pool::FullPool<Block, FullClient>,
+		FullPool,
 		(
 			sc_finality_grandpa::GrandpaBlockImport<
 				FullBackend,
@@ -59,10 +128,6 @@ pub fn new_partial(
 	>,
 	ServiceError,
 > {
-	if config.keystore_remote.is_some() {
-		return Err(ServiceError::Other(format!("Remote Keystores are not supported.")))
-	}
-
 	let telemetry = config
 		.telemetry_endpoints
 		.clone()
@@ -110,6 +175,25 @@ pub fn new_partial(
 		telemetry.as_ref().map(|x| x.handle()),
 	)?;
 
+	if sealing.is_some() {
+		let import_queue = sc_consensus_manual_seal::import_queue(
+			Box::new(client.clone()),
+			&task_manager.spawn_essential_handle(),
+			config.prometheus_registry(),
+		);
+
+		return Ok(sc_service::PartialComponents {
+			client,
+			backend,
+			task_manager,
+			import_queue,
+			keystore_container,
+			select_chain,
+			transaction_pool,
+			other: (grandpa_block_import, grandpa_link, telemetry),
+		});
+	}
+
 	let slot_duration = sc_consensus_aura::slot_duration(&*client)?.slot_duration();
 
 	let import_queue =
@@ -149,15 +233,19 @@ pub fn new_partial(
 	})
 }
 
-fn remote_keystore(_url: &String) -> Result<Arc<LocalKeystore>, &'static str> {
-	// FIXME: here would the concrete keystore be built,
-	//        must return a concrete type (NOT `LocalKeystore`) that
-	//        implements `CryptoStore` and `SyncCryptoStore`
-	Err("Remote Keystore not supported.")
+fn remote_keystore(url: &String) -> Result<Arc<RemoteKeystore>, String> {
+	RemoteKeystore::open(url).map(Arc::new)
 }
 
 /// Builds a new service for a full client.
-pub fn new_full(mut config: Configuration) -> Result<TaskManager, ServiceError> {
+///
+/// With `sealing` set the node authors its own blocks through manual seal instead of running
+/// Aura and GRANDPA. Otherwise GRANDPA runs with `grandpa_settings`.
+pub fn new_full(
+	mut config: Configuration,
+	sealing: Option<Sealing>,
+	grandpa_settings: GrandpaSettings,
+) -> Result<TaskManager, ServiceError> {
 	let sc_service::PartialComponents {
 		client,
 		backend,
@@ -167,7 +255,7 @@ pub fn new_full(mut config: Configuration) -> Result<TaskManager, ServiceError>
 		select_chain,
 		transaction_pool,
 		other: (block_import, grandpa_link, mut telemetry),
-	} = new_partial(&config)?;
+	} = new_partial(&config, sealing)?;
 
 	if let Some(url) = &config.keystore_remote {
 		match remote_keystore(url) {
@@ -214,13 +302,25 @@ pub fn new_full(mut config: Configuration) -> Result<TaskManager, ServiceError>
 	let enable_grandpa = !config.disable_grandpa;
 	let prometheus_registry = config.prometheus_registry().cloned();
 
+	let (seal_commands, command_sink) = match sealing {
+		Some(sealing) => {
+			let (commands, sink) = seal_commands(sealing, &transaction_pool);
+			(Some(commands), sink)
+		},
+		None => (None, None),
+	};
+
 	let rpc_extensions_builder = {
 		let client = client.clone();
 		let pool = transaction_pool.clone();
 
 		Box::new(move |deny_unsafe, _| {
-			let deps =
-				crate::rpc::FullDeps { client: client.clone(), pool: pool.clone(), deny_unsafe };
+			let deps = crate::rpc::FullDeps {
+				client: client.clone(),
+				pool: pool.clone(),
+				deny_unsafe,
+				command_sink: command_sink.clone(),
+			};
 
 			Ok(crate::rpc::create_full(deps))
 		})
@@ -239,6 +339,54 @@ pub fn new_full(mut config: Configuration) -> Result<TaskManager, ServiceError>
 		telemetry: telemetry.as_mut(),
 	})?;
 
+	if let Some(commands_stream) = seal_commands {
+		let proposer_factory = sc_basic_authorship::ProposerFactory::new(
+			task_manager.spawn_handle(),
+			client.clone(),
+			transaction_pool.clone(),
+			prometheus_registry.as_ref(),
+			telemetry.as_ref().map(|x| x.handle()),
+		);
+
+		let create_inherent_data_providers = {
+			let client = client.clone();
+			move |_, ()| {
+				let client = client.clone();
+				async move {
+					// Each block moves time on by one slot, so Aura accepts blocks sealed faster
+					// than the slot duration.
+					let timestamp =
+						SlotTimestampProvider::new_aura(client).map_err(|e| format!("{:?}", e))?;
+					let slot = sp_consensus_aura::inherents::InherentDataProvider::new(
+						timestamp.slot().into(),
+					);
+
+					Ok((timestamp, slot))
+				}
+			}
+		};
+
+		let manual_seal = sc_consensus_manual_seal::run_manual_seal(ManualSealParams {
+			block_import: client.clone(),
+			env: proposer_factory,
+			client: client.clone(),
+			pool: transaction_pool,
+			commands_stream,
+			select_chain,
+			consensus_data_provider: Some(Box::new(AuraConsensusDataProvider::new(client))),
+			create_inherent_data_providers,
+		});
+
+		task_manager.spawn_essential_handle().spawn_blocking(
+			"manual-seal",
+			Some("block-authoring"),
+			manual_seal,
+		);
+
+		network_starter.start_network();
+		return Ok(task_manager)
+	}
+
 	if role.is_authority() {
 		let proposer_factory = sc_basic_authorship::ProposerFactory::new(
 			task_manager.spawn_handle(),
@@ -297,9 +445,8 @@ pub fn new_full(mut config: Configuration) -> Result<TaskManager, ServiceError>
 		if role.is_authority() { Some(keystore_container.sync_keystore()) } else { None };
 
 	let grandpa_config = sc_finality_grandpa::Config {
-		// FIXME #1578 make this available through chainspec
-		gossip_duration: Duration::from_millis(333),
-		justification_period: 512,
+		gossip_duration: Duration::from_millis(grandpa_settings.gossip_duration),
+		justification_period: grandpa_settings.justification_period,
 		name: Some(name),
 		observer_enabled: false,
 		keystore,
@@ -308,6 +455,12 @@ pub fn new_full(mut config: Configuration) -> Result<TaskManager, ServiceError>
 	};
 
 	if enable_grandpa {
+		let mut voting_rule = sc_finality_grandpa::VotingRulesBuilder::new()
+			.add(sc_finality_grandpa::BeforeBestBlockBy(grandpa_settings.before_best_block_by));
+		if grandpa_settings.three_quarters_of_unfinalized_chain {
+			voting_rule = voting_rule.add(sc_finality_grandpa::ThreeQuartersOfTheUnfinalizedChain);
+		}
+
 		// start the full GRANDPA voter
 		// NOTE: non-authorities could run the GRANDPA observer protocol, but at
 		// this point the full voter should provide better guarantees of block
@@ -318,7 +471,7 @@ pub fn new_full(mut config: Configuration) -> Result<TaskManager, ServiceError>
 			config: grandpa_config,
 			link: grandpa_link,
 			network,
-			voting_rule: sc_finality_grandpa::VotingRulesBuilder::default().build(),
+			voting_rule: voting_rule.build(),
 			prometheus_registry,
 			shared_voter_state: SharedVoterState::empty(),
 			telemetry: telemetry.as_ref().map(|x| x.handle()),
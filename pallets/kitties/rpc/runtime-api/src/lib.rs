@@ -0,0 +1,48 @@
+//! 查询小猫的 Runtime API，供 `pallet-kitties-rpc` 调用
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::{Codec, Decode, Encode};
+use scale_info::TypeInfo;
+use sp_runtime::RuntimeDebug;
+use sp_std::vec::Vec;
+
+/// 分页查询时每页的小猫数量
+pub const PAGE_SIZE: u32 = 50;
+
+/// 一只小猫的完整信息
+#[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo)]
+pub struct KittyInfo<AccountId, Balance, BlockNumber> {
+	pub id: u32,
+	pub owner: AccountId,
+	pub dna: [u8; 16],
+	/// 挂单价格，None 表示没有出售
+	pub price: Option<Balance>,
+	pub parents: Option<(u32, u32)>,
+	pub generation: u32,
+	pub birth_block: BlockNumber,
+	pub creator: Option<AccountId>,
+}
+
+sp_api::decl_runtime_apis! {
+	pub trait KittiesApi<AccountId, Balance, BlockNumber> where
+		AccountId: Codec,
+		Balance: Codec,
+		BlockNumber: Codec,
+	{
+		/// 查询一只小猫，不存在或已销毁时返回 None
+		fn kitty(kitty_id: u32) -> Option<KittyInfo<AccountId, Balance, BlockNumber>>;
+
+		/// 查询某个账户拥有的小猫，第 `page` 页（从0开始）
+		fn kitties_by_owner(
+			owner: AccountId,
+			page: u32,
+		) -> Vec<KittyInfo<AccountId, Balance, BlockNumber>>;
+
+		/// 查询正在出售的小猫，第 `page` 页（从0开始）
+		fn listed_kitties(page: u32) -> Vec<KittyInfo<AccountId, Balance, BlockNumber>>;
+
+		/// 已经铸造的小猫数量，包括已销毁的。小猫索引从1开始连续分配，所以也是最大的小猫索引
+		fn kitties_count() -> u32;
+	}
+}
@@ -0,0 +1,196 @@
+//! 查询小猫的 RPC 接口，返回解码后的 JSON，而不是原始的 SCALE 存储
+
+use codec::Codec;
+use jsonrpc_core::{Error as RpcError, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+use pallet_kitties::phenotype::{decode_phenotype, Phenotype};
+use serde::{Deserialize, Serialize};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_core::Bytes;
+use sp_rpc::number::NumberOrHex;
+use sp_runtime::{generic::BlockId, traits::Block as BlockT};
+use std::{convert::TryInto, sync::Arc};
+
+pub use pallet_kitties_rpc_runtime_api::{KittiesApi as KittiesRuntimeApi, KittyInfo, PAGE_SIZE};
+
+/// RPC 返回的小猫信息
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Kitty<AccountId, BlockNumber> {
+	pub id: u32,
+	pub owner: AccountId,
+	/// 16字节的DNA，十六进制编码
+	pub dna: Bytes,
+	/// 挂单价格，None 表示没有出售
+	pub price: Option<NumberOrHex>,
+	pub parents: Option<(u32, u32)>,
+	pub generation: u32,
+	pub birth_block: BlockNumber,
+	pub creator: Option<AccountId>,
+	/// 用当前基因布局解码出的外观
+	pub phenotype: Phenotype,
+	pub rarity_score: u32,
+}
+
+#[rpc]
+pub trait KittiesApi<BlockHash, AccountId, BlockNumber> {
+	/// 查询一只小猫，不存在或已销毁时返回 null
+	#[rpc(name = "kitties_getKitty")]
+	fn kitty(
+		&self,
+		kitty_id: u32,
+		at: Option<BlockHash>,
+	) -> Result<Option<Kitty<AccountId, BlockNumber>>>;
+
+	/// 查询某个账户拥有的小猫，`page` 从0开始，每页 `PAGE_SIZE` 只
+	#[rpc(name = "kitties_getKittiesByOwner")]
+	fn kitties_by_owner(
+		&self,
+		owner: AccountId,
+		page: u32,
+		at: Option<BlockHash>,
+	) -> Result<Vec<Kitty<AccountId, BlockNumber>>>;
+
+	/// 查询正在出售的小猫，`page` 从0开始，每页 `PAGE_SIZE` 只
+	#[rpc(name = "kitties_getListed")]
+	fn listed(
+		&self,
+		page: u32,
+		at: Option<BlockHash>,
+	) -> Result<Vec<Kitty<AccountId, BlockNumber>>>;
+
+	/// 已经铸造的小猫数量，包括已销毁的
+	#[rpc(name = "kitties_count")]
+	fn count(&self, at: Option<BlockHash>) -> Result<u32>;
+}
+
+/// 通过 `KittiesRuntimeApi` 实现的小猫 RPC
+pub struct Kitties<C, Block> {
+	client: Arc<C>,
+	_marker: std::marker::PhantomData<Block>,
+}
+
+impl<C, Block> Kitties<C, Block> {
+	pub fn new(client: Arc<C>) -> Self {
+		Self { client, _marker: Default::default() }
+	}
+}
+
+/// RPC 错误码
+pub enum Error {
+	/// 调用 Runtime API 失败
+	RuntimeError,
+	/// 价格无法用 JSON 表示
+	PriceConversionError,
+}
+
+impl From<Error> for i64 {
+	fn from(e: Error) -> i64 {
+		match e {
+			Error::RuntimeError => 1,
+			Error::PriceConversionError => 2,
+		}
+	}
+}
+
+fn runtime_error(e: impl std::fmt::Debug) -> RpcError {
+	RpcError {
+		code: ErrorCode::ServerError(Error::RuntimeError.into()),
+		message: "Unable to query kitties.".into(),
+		data: Some(format!("{:?}", e).into()),
+	}
+}
+
+fn to_rpc<AccountId, Balance, BlockNumber>(
+	info: KittyInfo<AccountId, Balance, BlockNumber>,
+) -> Result<Kitty<AccountId, BlockNumber>>
+where
+	Balance: Copy + std::fmt::Debug + TryInto<NumberOrHex>,
+{
+	let price = info
+		.price
+		.map(|price| {
+			price.try_into().map_err(|_| RpcError {
+				code: ErrorCode::ServerError(Error::PriceConversionError.into()),
+				message: format!("{:?} doesn't fit in NumberOrHex representation", price),
+				data: None,
+			})
+		})
+		.transpose()?;
+
+	let phenotype = decode_phenotype(&info.dna);
+
+	Ok(Kitty {
+		id: info.id,
+		owner: info.owner,
+		dna: Bytes(info.dna.to_vec()),
+		price,
+		parents: info.parents,
+		generation: info.generation,
+		birth_block: info.birth_block,
+		creator: info.creator,
+		phenotype,
+		rarity_score: phenotype.rarity_score(),
+	})
+}
+
+impl<C, Block, AccountId, Balance, BlockNumber>
+	KittiesApi<<Block as BlockT>::Hash, AccountId, BlockNumber> for Kitties<C, Block>
+where
+	Block: BlockT,
+	C: 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block>,
+	C::Api: KittiesRuntimeApi<Block, AccountId, Balance, BlockNumber>,
+	AccountId: Codec + Send + Sync + 'static,
+	Balance: Codec + Copy + std::fmt::Debug + TryInto<NumberOrHex>,
+	BlockNumber: Codec + Send + Sync + 'static,
+{
+	fn kitty(
+		&self,
+		kitty_id: u32,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Option<Kitty<AccountId, BlockNumber>>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		api.kitty(&at, kitty_id).map_err(runtime_error)?.map(to_rpc).transpose()
+	}
+
+	fn kitties_by_owner(
+		&self,
+		owner: AccountId,
+		page: u32,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Vec<Kitty<AccountId, BlockNumber>>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		api.kitties_by_owner(&at, owner, page)
+			.map_err(runtime_error)?
+			.into_iter()
+			.map(to_rpc)
+			.collect()
+	}
+
+	fn listed(
+		&self,
+		page: u32,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Vec<Kitty<AccountId, BlockNumber>>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		api.listed_kitties(&at, page)
+			.map_err(runtime_error)?
+			.into_iter()
+			.map(to_rpc)
+			.collect()
+	}
+
+	fn count(&self, at: Option<<Block as BlockT>::Hash>) -> Result<u32> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		api.kitties_count(&at).map_err(runtime_error)
+	}
+}
@@ -0,0 +1,233 @@
+//! Benchmarking setup for pallet-kitties
+
+use super::*;
+
+use frame_benchmarking::{account, benchmarks, whitelisted_caller};
+use frame_support::{
+	sp_runtime::traits::Bounded,
+	traits::{Currency, Get, Hooks},
+	weights::Weight,
+};
+use frame_system::RawOrigin;
+use sp_std::vec::Vec;
+
+const SEED: u32 = 0;
+
+// 给账户充足的余额，用于质押押金和购买小猫
+fn funded_account<T: Config>(name: &'static str, index: u32) -> T::AccountId {
+	let who: T::AccountId = account(name, index, SEED);
+	T::Currency::make_free_balance_be(&who, BalanceOf::<T>::max_value() / 2u32.into());
+	who
+}
+
+fn funded_caller<T: Config>() -> T::AccountId {
+	let caller: T::AccountId = whitelisted_caller();
+	T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value() / 2u32.into());
+	caller
+}
+
+benchmarks! {
+	create {
+		let caller = funded_caller::<T>();
+	}: _(RawOrigin::Signed(caller.clone()))
+	verify {
+		assert_eq!(Owner::<T>::get(1), Some(caller));
+	}
+
+	create_batch {
+		let n in 1 .. T::MaxBatchSize::get();
+		let caller = funded_caller::<T>();
+	}: _(RawOrigin::Signed(caller.clone()), n)
+	verify {
+		assert_eq!(OwnedKitties::<T>::get(caller).len() as u32, n);
+	}
+
+	// 最坏情况: 其中一只小猫属于别人，需要支付配种费
+	breed {
+		let caller = funded_caller::<T>();
+		Pallet::<T>::create(RawOrigin::Signed(caller.clone()).into())?;
+		let sire_owner = funded_account::<T>("sire_owner", 0);
+		Pallet::<T>::create(RawOrigin::Signed(sire_owner.clone()).into())?;
+		Pallet::<T>::offer_sire(RawOrigin::Signed(sire_owner).into(), 2, None, 100u32.into())?;
+	}: _(RawOrigin::Signed(caller.clone()), 1, 2)
+	verify {
+		assert_eq!(Owner::<T>::get(3), Some(caller));
+	}
+
+	set_price {
+		let caller = funded_caller::<T>();
+		Pallet::<T>::create(RawOrigin::Signed(caller.clone()).into())?;
+	}: _(RawOrigin::Signed(caller), 1, 100u32.into())
+	verify {
+		assert_eq!(Kitties::<T>::get(1).unwrap().price, Some(100u32.into()));
+	}
+
+	unlist {
+		let caller = funded_caller::<T>();
+		Pallet::<T>::create(RawOrigin::Signed(caller.clone()).into())?;
+		Pallet::<T>::set_price(RawOrigin::Signed(caller.clone()).into(), 1, 100u32.into())?;
+	}: _(RawOrigin::Signed(caller), 1)
+	verify {
+		assert_eq!(Kitties::<T>::get(1).unwrap().price, None);
+	}
+
+	// 最坏情况: 转售，需要支付市场手续费和版税
+	buy_kitty {
+		let creator = funded_account::<T>("creator", 0);
+		Pallet::<T>::create(RawOrigin::Signed(creator.clone()).into())?;
+		let seller = funded_account::<T>("seller", 0);
+		Pallet::<T>::transfer(RawOrigin::Signed(creator).into(), seller.clone(), 1)?;
+		Pallet::<T>::set_price(RawOrigin::Signed(seller).into(), 1, 1_000u32.into())?;
+		let caller = funded_caller::<T>();
+	}: _(RawOrigin::Signed(caller.clone()), 1, 1_000u32.into())
+	verify {
+		assert_eq!(Owner::<T>::get(1), Some(caller));
+	}
+
+	transfer {
+		let caller = funded_caller::<T>();
+		Pallet::<T>::create(RawOrigin::Signed(caller.clone()).into())?;
+		let recipient = funded_account::<T>("recipient", 0);
+	}: _(RawOrigin::Signed(caller), recipient.clone(), 1)
+	verify {
+		assert_eq!(Owner::<T>::get(1), Some(recipient));
+	}
+
+	// 每只小猫都挂单出售，并且送给不同的人
+	transfer_batch {
+		let n in 1 .. T::MaxBatchSize::get();
+		let caller = funded_caller::<T>();
+		Pallet::<T>::create_batch(RawOrigin::Signed(caller.clone()).into(), n)?;
+		let mut transfers = Vec::new();
+		for i in 0 .. n {
+			let kitty_id = i + 1;
+			Pallet::<T>::set_price(RawOrigin::Signed(caller.clone()).into(), kitty_id, 100u32.into())?;
+			transfers.push((funded_account::<T>("recipient", i), kitty_id));
+		}
+	}: _(RawOrigin::Signed(caller.clone()), transfers)
+	verify {
+		assert!(OwnedKitties::<T>::get(caller).is_empty());
+	}
+
+	offer_sire {
+		let caller = funded_caller::<T>();
+		Pallet::<T>::create(RawOrigin::Signed(caller.clone()).into())?;
+		let breeder: T::AccountId = account("breeder", 0, SEED);
+	}: _(RawOrigin::Signed(caller), 1, Some(breeder.clone()), 100u32.into())
+	verify {
+		assert_eq!(SireOffers::<T>::get(1).unwrap().breeder, Some(breeder));
+	}
+
+	cancel_sire_offer {
+		let caller = funded_caller::<T>();
+		Pallet::<T>::create(RawOrigin::Signed(caller.clone()).into())?;
+		Pallet::<T>::offer_sire(RawOrigin::Signed(caller.clone()).into(), 1, None, 100u32.into())?;
+	}: _(RawOrigin::Signed(caller), 1)
+	verify {
+		assert!(SireOffers::<T>::get(1).is_none());
+	}
+
+	burn {
+		let caller = funded_caller::<T>();
+		Pallet::<T>::create(RawOrigin::Signed(caller.clone()).into())?;
+		Pallet::<T>::offer_sire(RawOrigin::Signed(caller.clone()).into(), 1, None, 100u32.into())?;
+	}: _(RawOrigin::Signed(caller), 1)
+	verify {
+		assert!(Kitties::<T>::get(1).is_none());
+	}
+
+	create_auction {
+		let caller = funded_caller::<T>();
+		Pallet::<T>::create(RawOrigin::Signed(caller.clone()).into())?;
+		Pallet::<T>::set_price(RawOrigin::Signed(caller.clone()).into(), 1, 100u32.into())?;
+	}: _(RawOrigin::Signed(caller), 1, 100u32.into(), 10u32.into())
+	verify {
+		assert!(Auctions::<T>::contains_key(1));
+	}
+
+	// 最坏情况: 需要退还上一个最高出价
+	bid {
+		let seller = funded_account::<T>("seller", 0);
+		Pallet::<T>::create(RawOrigin::Signed(seller.clone()).into())?;
+		Pallet::<T>::create_auction(RawOrigin::Signed(seller).into(), 1, 100u32.into(), 10u32.into())?;
+		let previous = funded_account::<T>("bidder", 0);
+		Pallet::<T>::bid(RawOrigin::Signed(previous).into(), 1, 100u32.into())?;
+		let caller = funded_caller::<T>();
+	}: _(RawOrigin::Signed(caller.clone()), 1, 200u32.into())
+	verify {
+		assert_eq!(Auctions::<T>::get(1).unwrap().top_bid, Some((caller, 200u32.into())));
+	}
+
+	// 每个结算的拍卖都是转售并且有出价，需要完成交割并支付版税
+	on_initialize {
+		let n in 0 .. T::MaxAuctionsEndingPerBlock::get();
+		let end_block: T::BlockNumber = 10u32.into();
+		let creator = funded_account::<T>("creator", 0);
+		for i in 0 .. n {
+			let seller = funded_account::<T>("seller", i);
+			Pallet::<T>::create(RawOrigin::Signed(creator.clone()).into())?;
+			let kitty_id = i + 1;
+			Pallet::<T>::transfer(RawOrigin::Signed(creator.clone()).into(), seller.clone(), kitty_id)?;
+			Pallet::<T>::create_auction(RawOrigin::Signed(seller).into(), kitty_id, 100u32.into(), end_block)?;
+			let bidder = funded_account::<T>("bidder", i);
+			Pallet::<T>::bid(RawOrigin::Signed(bidder).into(), kitty_id, 100u32.into())?;
+		}
+	}: {
+		Pallet::<T>::on_initialize(end_block);
+	}
+	verify {
+		assert_eq!(Auctions::<T>::iter().count(), 0);
+	}
+
+	make_offer {
+		let owner = funded_account::<T>("owner", 0);
+		Pallet::<T>::create(RawOrigin::Signed(owner).into())?;
+		let caller = funded_caller::<T>();
+	}: _(RawOrigin::Signed(caller.clone()), 1, 100u32.into(), 10u32.into())
+	verify {
+		assert!(Offers::<T>::contains_key(1, caller));
+	}
+
+	// 最坏情况: 转售，需要支付市场手续费和版税
+	accept_offer {
+		let creator = funded_account::<T>("creator", 0);
+		Pallet::<T>::create(RawOrigin::Signed(creator.clone()).into())?;
+		let caller = funded_caller::<T>();
+		Pallet::<T>::transfer(RawOrigin::Signed(creator).into(), caller.clone(), 1)?;
+		let offerer = funded_account::<T>("offerer", 0);
+		Pallet::<T>::make_offer(RawOrigin::Signed(offerer.clone()).into(), 1, 1_000u32.into(), 10u32.into())?;
+	}: _(RawOrigin::Signed(caller), 1, offerer.clone())
+	verify {
+		assert_eq!(Owner::<T>::get(1), Some(offerer));
+	}
+
+	withdraw_offer {
+		let owner = funded_account::<T>("owner", 0);
+		Pallet::<T>::create(RawOrigin::Signed(owner).into())?;
+		let caller = funded_caller::<T>();
+		Pallet::<T>::make_offer(RawOrigin::Signed(caller.clone()).into(), 1, 100u32.into(), 10u32.into())?;
+	}: _(RawOrigin::Signed(caller.clone()), 1)
+	verify {
+		assert!(!Offers::<T>::contains_key(1, caller));
+	}
+
+	// 清理一个区块中失效的 n 个报价
+	expire_offers {
+		let n in 0 .. T::MaxOffersExpiringPerBlock::get();
+		let owner = funded_account::<T>("owner", 0);
+		Pallet::<T>::create(RawOrigin::Signed(owner).into())?;
+		let expires_at: T::BlockNumber = 10u32.into();
+		for i in 0 .. n {
+			let offerer = funded_account::<T>("offerer", i);
+			Pallet::<T>::make_offer(RawOrigin::Signed(offerer).into(), 1, 100u32.into(), expires_at)?;
+		}
+		NextOfferCleanup::<T>::put(expires_at);
+	}: {
+		Pallet::<T>::on_idle(expires_at, Weight::max_value());
+	}
+	verify {
+		assert_eq!(Offers::<T>::iter().count(), 0);
+	}
+
+	impl_benchmark_test_suite!(Pallet, crate::mock::new_test_ext(), crate::mock::Test);
+}
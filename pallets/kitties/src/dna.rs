@@ -0,0 +1,64 @@
+//! 小猫基因混合算法
+
+use crate::phenotype::GeneLayout;
+use codec::Encode;
+use frame_support::traits::Get;
+use sp_io::hashing::blake2_256;
+use sp_runtime::Perbill;
+use sp_std::marker::PhantomData;
+
+/// 繁殖时由父母的DNA生成孩子的DNA，运行时可以选择自己的遗传规则
+pub trait DnaMixer {
+	/// `selector` 是每次繁殖生成的随机数
+	fn mix(dna_1: &[u8; 16], dna_2: &[u8; 16], selector: &[u8; 16]) -> [u8; 16];
+}
+
+/// 按位选择: selector 为 1 的位取自 dna_1，为 0 的位取自 dna_2
+pub struct BitSelectMixer;
+
+impl DnaMixer for BitSelectMixer {
+	fn mix(dna_1: &[u8; 16], dna_2: &[u8; 16], selector: &[u8; 16]) -> [u8; 16] {
+		let mut new_dna = [0u8; 16];
+
+		for (i, gene) in new_dna.iter_mut().enumerate() {
+			*gene = (selector[i] & dna_1[i]) | (!selector[i] & dna_2[i]);
+		}
+
+		new_dna
+	}
+}
+
+/// 孟德尔遗传: 按 `GeneLayout::CURRENT`，每个基因的第一个等位基因随机取自 dna_1 的一对，
+/// 第二个随机取自 dna_2 的一对，表现出哪个性状由显隐性决定。每个等位基因以 `R` 给出的概率
+/// 突变成随机值。不属于任何基因的保留字节按 `BitSelectMixer` 混合
+pub struct MendelianMixer<R>(PhantomData<R>);
+
+impl<R: Get<Perbill>> DnaMixer for MendelianMixer<R> {
+	fn mix(dna_1: &[u8; 16], dna_2: &[u8; 16], selector: &[u8; 16]) -> [u8; 16] {
+		let mut new_dna = BitSelectMixer::mix(dna_1, dna_2, selector);
+		let mutation_rate = R::get();
+
+		for (i, &gene) in GeneLayout::CURRENT.genes().iter().enumerate() {
+			// 每个基因从 selector 派生出一组独立的随机数:
+			// 第0字节选择等位基因，之后每个等位基因用4字节决定是否突变、1字节作为突变结果
+			let random = (selector, i as u32).using_encoded(blake2_256);
+			let inherited = [
+				dna_1[gene + (random[0] & 1) as usize],
+				dna_2[gene + ((random[0] >> 1) & 1) as usize],
+			];
+
+			for (j, allele) in inherited.into_iter().enumerate() {
+				let roll = u32::from_le_bytes([
+					random[1 + j * 5],
+					random[2 + j * 5],
+					random[3 + j * 5],
+					random[4 + j * 5],
+				]);
+				let mutates = Perbill::from_parts(roll % 1_000_000_000) < mutation_rate;
+				new_dna[gene + j] = if mutates { random[5 + j * 5] } else { allele };
+			}
+		}
+
+		new_dna
+	}
+}
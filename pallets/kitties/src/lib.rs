@@ -2,19 +2,53 @@
 
 pub use pallet::*;
 
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+
+pub mod dna;
+pub mod migrations;
+pub mod phenotype;
+pub mod weights;
+
 #[frame_support::pallet]
 pub mod pallet {
 	use codec::{Decode, Encode};
 	use frame_support::pallet_prelude::*;
-	use frame_support::traits::{tokens::ExistenceRequirement, Currency, Randomness};
-	use frame_support::transactional;
+	use frame_support::traits::{
+		tokens::{ExistenceRequirement, WithdrawReasons},
+		Currency, OnUnbalanced, Randomness, ReservableCurrency,
+	};
+	use frame_support::{
+		storage::{with_transaction, TransactionOutcome},
+		transactional,
+	};
 	use frame_system::pallet_prelude::*;
 	use scale_info::TypeInfo;
 	use sp_io::hashing::blake2_128;
+	use sp_runtime::{
+		traits::{Saturating, Zero},
+		Perbill,
+	};
+	use sp_std::{collections::btree_set::BTreeSet, vec::Vec};
+
+	pub use crate::{dna::DnaMixer, weights::WeightInfo};
+	use crate::{
+		migrations,
+		phenotype::{decode_phenotype, Phenotype},
+	};
 
-	type KittyIndex = u32;
-	type BalanceOf<T> =
+	pub type KittyIndex = u32;
+	pub type BalanceOf<T> =
 		<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+	pub type NegativeImbalanceOf<T> = <<T as Config>::Currency as Currency<
+		<T as frame_system::Config>::AccountId,
+	>>::NegativeImbalance;
 
 	/// 小猫 基因
 	#[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo)]
@@ -22,18 +56,99 @@ pub mod pallet {
 	pub struct Kitty<T: Config> {
 		pub dna: [u8; 16],
 		pub price: Option<BalanceOf<T>>,
+		/// 父母的索引，创建的小猫没有父母
+		pub parents: Option<(KittyIndex, KittyIndex)>,
+		/// 第几代，创建的小猫是第0代
+		pub generation: u32,
+		/// 出生时的区块高度
+		pub birth_block: T::BlockNumber,
+		/// 铸造这只小猫的账户，转售时收取版税。升级前铸造的小猫没有记录
+		pub creator: Option<T::AccountId>,
+	}
+
+	/// 配种授权: 允许别人用自己的小猫繁殖
+	#[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo)]
+	#[scale_info(skip_type_params(T))]
+	pub struct SireOffer<T: Config> {
+		/// 允许繁殖的账户，None 表示任何人
+		pub breeder: Option<T::AccountId>,
+		/// 每次繁殖需要支付给主人的配种费
+		pub fee: BalanceOf<T>,
+	}
+
+	/// 对小猫的报价，报价金额在报价人账户中被锁定
+	#[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo)]
+	#[scale_info(skip_type_params(T))]
+	pub struct Offer<T: Config> {
+		pub amount: BalanceOf<T>,
+		/// 从这个区块开始报价失效，之后会在 on_idle 中清理
+		pub expires_at: T::BlockNumber,
+	}
+
+	/// 英式拍卖: 出价只能越来越高，结束时价高者得
+	#[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug, TypeInfo)]
+	#[scale_info(skip_type_params(T))]
+	pub struct Auction<T: Config> {
+		/// 卖家，也就是拍卖开始时小猫的主人
+		pub seller: T::AccountId,
+		/// 起拍价，出价不能低于这个价格
+		pub reserve: BalanceOf<T>,
+		/// 在这个区块开始时结算
+		pub end_block: T::BlockNumber,
+		/// 当前最高出价: (出价人, 金额)，金额在出价人账户中被锁定
+		pub top_bid: Option<(T::AccountId, BalanceOf<T>)>,
 	}
 
 	#[pallet::config]
 	pub trait Config: frame_system::Config {
 		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
 		// Balance实现
-		type Currency: Currency<Self::AccountId>;
+		type Currency: ReservableCurrency<Self::AccountId>;
 		type Randomness: Randomness<Self::Hash, Self::BlockNumber>;
+		/// 繁殖时使用的基因混合算法
+		type DnaMixer: DnaMixer;
+		/// 每只小猫需要质押的押金，修改后不影响已经质押的押金
+		#[pallet::constant]
+		type KittyReserve: Get<BalanceOf<Self>>;
+		/// 每个账户最多拥有的小猫数量
+		#[pallet::constant]
+		type MaxKittiesOwned: Get<u32>;
+		/// 第0代小猫繁殖后需要休息的区块数，第n代休息 n + 1 倍的时间
+		#[pallet::constant]
+		type BreedCooldown: Get<Self::BlockNumber>;
+		/// 同一个区块最多结算的拍卖数量
+		#[pallet::constant]
+		type MaxAuctionsEndingPerBlock: Get<u32>;
+		/// 拍卖最长的区块数，小猫和最高出价人的金额最多被锁定这么久
+		#[pallet::constant]
+		type MaxAuctionDuration: Get<Self::BlockNumber>;
+		/// 同一个区块最多失效的报价数量
+		#[pallet::constant]
+		type MaxOffersExpiringPerBlock: Get<u32>;
+		/// 报价最长的有效区块数。小猫被销毁后，针对它的报价最迟在这之后失效并退还锁定的金额
+		#[pallet::constant]
+		type MaxOfferDuration: Get<Self::BlockNumber>;
+		/// 每笔交易收取的市场手续费比例，从卖家所得中扣除，买家只支付售价
+		#[pallet::constant]
+		type MarketplaceFee: Get<Perbill>;
+		/// 收到的市场手续费如何处理，比如转入国库
+		type OnMarketplaceFee: OnUnbalanced<NegativeImbalanceOf<Self>>;
+		/// 每次转售支付给小猫创作者的版税比例，从卖家所得中扣除
+		#[pallet::constant]
+		type CreatorRoyalty: Get<Perbill>;
+		/// 批量创建和批量转让的最大数量
+		#[pallet::constant]
+		type MaxBatchSize: Get<u32>;
+		/// 交易权重
+		type WeightInfo: WeightInfo;
 	}
 
+	/// 当前的存储版本
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(3);
+
 	#[pallet::pallet]
 	#[pallet::generate_store(pub (super) trait Store)]
+	#[pallet::storage_version(STORAGE_VERSION)]
 	pub struct Pallet<T>(_);
 
 	/// 小猫现有数量
@@ -51,50 +166,239 @@ pub mod pallet {
 	#[pallet::getter(fn owner)]
 	pub type Owner<T: Config> = StorageMap<_, Blake2_128Concat, KittyIndex, T::AccountId>;
 
+	/// 主人: 拥有的小猫索引列表
+	#[pallet::storage]
+	#[pallet::getter(fn owned_kitties)]
+	pub type OwnedKitties<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		BoundedVec<KittyIndex, T::MaxKittiesOwned>,
+		ValueQuery,
+	>;
+
+	/// 小猫索引: 主人为这只小猫质押的押金，更早铸造的小猫没有质押，记为 0
+	#[pallet::storage]
+	#[pallet::getter(fn kitty_deposit)]
+	pub type KittyDeposits<T: Config> =
+		StorageMap<_, Blake2_128Concat, KittyIndex, BalanceOf<T>, ValueQuery>;
+
+	/// 小猫索引: 配种授权
+	#[pallet::storage]
+	#[pallet::getter(fn sire_offers)]
+	pub type SireOffers<T: Config> = StorageMap<_, Blake2_128Concat, KittyIndex, SireOffer<T>>;
+
+	/// 小猫索引: 下一次可以繁殖的区块高度
+	#[pallet::storage]
+	#[pallet::getter(fn next_breedable_at)]
+	pub type NextBreedableAt<T: Config> =
+		StorageMap<_, Blake2_128Concat, KittyIndex, T::BlockNumber, ValueQuery>;
+
+	/// 小猫索引: 正在进行的拍卖，拍卖期间小猫不能出售、转让和繁殖
+	#[pallet::storage]
+	#[pallet::getter(fn auctions)]
+	pub type Auctions<T: Config> = StorageMap<_, Blake2_128Concat, KittyIndex, Auction<T>>;
+
+	/// 区块高度: 在该区块结算的拍卖
+	#[pallet::storage]
+	#[pallet::getter(fn auctions_ending_at)]
+	pub type AuctionsEndingAt<T: Config> = StorageMap<
+		_,
+		Twox64Concat,
+		T::BlockNumber,
+		BoundedVec<KittyIndex, T::MaxAuctionsEndingPerBlock>,
+		ValueQuery,
+	>;
+
+	/// (小猫索引, 报价人): 报价
+	#[pallet::storage]
+	#[pallet::getter(fn offers)]
+	pub type Offers<T: Config> =
+		StorageDoubleMap<_, Blake2_128Concat, KittyIndex, Blake2_128Concat, T::AccountId, Offer<T>>;
+
+	/// 区块高度: 在该区块失效的报价
+	#[pallet::storage]
+	#[pallet::getter(fn offers_expiring_at)]
+	pub type OffersExpiringAt<T: Config> = StorageMap<
+		_,
+		Twox64Concat,
+		T::BlockNumber,
+		BoundedVec<(KittyIndex, T::AccountId), T::MaxOffersExpiringPerBlock>,
+		ValueQuery,
+	>;
+
+	/// 下一个需要清理失效报价的区块高度
+	#[pallet::storage]
+	#[pallet::getter(fn next_offer_cleanup)]
+	pub type NextOfferCleanup<T: Config> = StorageValue<_, T::BlockNumber>;
+
+	/// 创世小猫: (主人, dna, 售价)
+	#[pallet::genesis_config]
+	pub struct GenesisConfig<T: Config> {
+		pub kitties: Vec<(T::AccountId, [u8; 16], Option<BalanceOf<T>>)>,
+	}
+
+	#[cfg(feature = "std")]
+	impl<T: Config> Default for GenesisConfig<T> {
+		fn default() -> Self {
+			Self { kitties: Vec::new() }
+		}
+	}
+
+	#[pallet::genesis_build]
+	impl<T: Config> GenesisBuild<T> for GenesisConfig<T> {
+		fn build(&self) {
+			// 创世小猫的dna不能重复
+			let mut dnas = BTreeSet::new();
+			for (_, dna, _) in &self.kitties {
+				assert!(dnas.insert(*dna), "Duplicate dna in genesis kitties");
+			}
+
+			for (owner, dna, price) in &self.kitties {
+				let kitty_id = Pallet::<T>::mint(owner, *dna, None, 0)
+					.expect("Genesis kitty owner must be able to hold and reserve for the kitty");
+
+				if let Some(price) = price {
+					assert!(*price > 0u32.into(), "Genesis kitty price can not be zero");
+					Kitties::<T>::mutate(kitty_id, |kitty| {
+						if let Some(kitty) = kitty {
+							kitty.price = Some(*price);
+						}
+					});
+				}
+			}
+		}
+	}
+
 	#[pallet::event]
 	#[pallet::generate_deposit(pub (super) fn deposit_event)]
 	pub enum Event<T: Config> {
 		KittyCreate(T::AccountId, KittyIndex),
 		Transfer(T::AccountId, KittyIndex, T::AccountId),
-		BreedSuccess(T::AccountId, KittyIndex, KittyIndex),
+		BreedSuccess(T::AccountId, KittyIndex, KittyIndex, KittyIndex),
 		SetPriceSuccess(T::AccountId, KittyIndex, BalanceOf<T>),
-		TransferSuccess(T::AccountId, T::AccountId, KittyIndex),
+		/// 批量创建: (主人, 第一只小猫, 数量)，小猫索引是连续的
+		BatchCreated(T::AccountId, KittyIndex, u32),
+		/// 批量转让: (原主人, 数量)
+		BatchTransferred(T::AccountId, u32),
+		/// 取消出售: (主人, 小猫)
+		KittyUnlisted(T::AccountId, KittyIndex),
+		/// 销毁小猫: (主人, 小猫)
+		KittyBurned(T::AccountId, KittyIndex),
+		/// 成交: (买家, 卖家, 小猫, 价格, 市场手续费, 版税)
+		KittySold(T::AccountId, T::AccountId, KittyIndex, BalanceOf<T>, BalanceOf<T>, BalanceOf<T>),
+		SireOfferSet(T::AccountId, KittyIndex, Option<T::AccountId>, BalanceOf<T>),
+		SireOfferCancelled(T::AccountId, KittyIndex),
+		SireFeePaid(T::AccountId, T::AccountId, KittyIndex, BalanceOf<T>),
+		/// 开始拍卖: (卖家, 小猫, 起拍价, 结束区块)
+		AuctionCreated(T::AccountId, KittyIndex, BalanceOf<T>, T::BlockNumber),
+		/// 出价: (出价人, 小猫, 金额)
+		BidPlaced(T::AccountId, KittyIndex, BalanceOf<T>),
+		/// 拍卖成交: (卖家, 买家, 小猫, 成交价, 市场手续费, 版税)
+		AuctionWon(
+			T::AccountId,
+			T::AccountId,
+			KittyIndex,
+			BalanceOf<T>,
+			BalanceOf<T>,
+			BalanceOf<T>,
+		),
+		/// 流拍，小猫留在卖家手里: (卖家, 小猫)
+		AuctionUnsold(T::AccountId, KittyIndex),
+		/// 报价: (报价人, 小猫, 金额, 失效区块)
+		OfferMade(T::AccountId, KittyIndex, BalanceOf<T>, T::BlockNumber),
+		/// 接受报价: (卖家, 报价人, 小猫, 金额, 市场手续费, 版税)
+		OfferAccepted(
+			T::AccountId,
+			T::AccountId,
+			KittyIndex,
+			BalanceOf<T>,
+			BalanceOf<T>,
+			BalanceOf<T>,
+		),
+		/// 撤回报价: (报价人, 小猫)
+		OfferWithdrawn(T::AccountId, KittyIndex),
+		/// 报价失效，退还锁定的金额: (报价人, 小猫)
+		OfferExpired(T::AccountId, KittyIndex),
 	}
 
 	#[pallet::error]
 	pub enum Error<T> {
-		KittiesCountOverflow, // 系统预留最大小猫数量溢出
-		CanNotYourSelf,       // 调用方不能是自己
-		NotOwner,             // 你不是这个小猫的主人
-		GenesCanNotSame,      // 小猫的父亲和母亲不能是同一个
-		InvalidKittyIndex,    // 不存在这个小猫
-		PriceNotZero,         // 售卖价格不能为0
-		PriceIsNone,          // 小猫没有设置价格
-		MoneyNotEnough,       // 买家的钱不够买小猫
+		KittiesCountOverflow,       // 系统预留最大小猫数量溢出
+		CanNotYourSelf,             // 调用方不能是自己
+		NotOwner,                   // 你不是这个小猫的主人
+		GenesCanNotSame,            // 小猫的父亲和母亲不能是同一个
+		InvalidKittyIndex,          // 不存在这个小猫
+		PriceNotZero,               // 售卖价格不能为0
+		PriceIsNone,                // 小猫没有设置价格
+		MoneyNotEnough,             // 买家的钱不够买小猫
+		ExceedMaxKittiesOwned,      // 拥有的小猫数量超过上限
+		NotEnoughBalanceForReserve, // 余额不足以质押押金
+		NotAllowedToBreed,          // 没有权限使用这只小猫繁殖
+		SireOfferNotExist,          // 这只小猫没有配种授权
+		BreedCooldownNotExpired,    // 小猫还在繁殖冷却期
+		KittyInAuction,             // 小猫正在拍卖
+		AuctionNotExist,            // 这只小猫没有在拍卖
+		InvalidAuctionEnd,          // 拍卖结束区块必须在未来
+		AuctionEnded,               // 拍卖已经结束
+		BidTooLow,                  // 出价低于起拍价或当前最高价
+		TooManyAuctionsEnding,      // 该区块结算的拍卖太多
+		PriceTooHigh,               // 售价高于买家愿意支付的最高价格
+		OfferNotExist,              // 没有这个报价
+		OfferAlreadyExists,         // 已经对这只小猫报过价，需要先撤回
+		InvalidOfferExpiry,         // 报价失效区块必须在未来
+		OfferExpired,               // 报价已经失效
+		TooManyOffersExpiring,      // 该区块失效的报价太多
+		EmptyBatch,                 // 批量操作不能为空
+		BatchTooLarge,              // 批量操作超过最大数量
+		OfferDurationTooLong,       // 报价有效期超过上限
+		AuctionDurationTooLong,     // 拍卖时间超过上限
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		/// 结算在本区块结束的拍卖
+		fn on_initialize(now: T::BlockNumber) -> Weight {
+			let ending = AuctionsEndingAt::<T>::take(now);
+			for kitty_id in ending.iter() {
+				Self::settle_auction(*kitty_id);
+			}
+
+			T::WeightInfo::on_initialize(ending.len() as u32)
+		}
+
+		/// 用剩余的区块权重清理失效的报价
+		fn on_idle(now: T::BlockNumber, remaining_weight: Weight) -> Weight {
+			Self::expire_offers(now, remaining_weight)
+		}
+
+		fn on_runtime_upgrade() -> Weight {
+			migrations::migrate::<T>()
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<(), &'static str> {
+			migrations::pre_upgrade::<T>()
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade() -> Result<(), &'static str> {
+			migrations::post_upgrade::<T>()
+		}
 	}
 
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
 		/// 创建小猫
-		#[pallet::weight(0)]
+		#[transactional]
+		#[pallet::weight(T::WeightInfo::create())]
 		pub fn create(origin: OriginFor<T>) -> DispatchResult {
 			let who = ensure_signed(origin)?;
 
-			// 获得 当前小猫id
-			let kitty_id = match Self::kitties_count() {
-				None => 1,
-				Some(index) => {
-					ensure!(index != KittyIndex::max_value(), Error::<T>::KittiesCountOverflow);
-					index
-				}
-			};
-
 			// 随机生成小猫DNA
 			let dna = Self::gen_dna();
 
-			Kitties::<T>::insert(kitty_id, Kitty::<T> { dna, price: None });
-			Owner::<T>::insert(kitty_id, who.clone());
-			KittiesCount::<T>::put(kitty_id + 1);
+			let kitty_id = Self::mint(&who, dna, None, 0)?;
 
 			Self::deposit_event(Event::KittyCreate(who, kitty_id));
 
@@ -102,7 +406,8 @@ pub mod pallet {
 		}
 
 		/// 繁殖小猫
-		#[pallet::weight(0)]
+		#[transactional]
+		#[pallet::weight(T::WeightInfo::breed())]
 		pub fn breed(
 			origin: OriginFor<T>,
 			kitty_id_1: KittyIndex,
@@ -117,32 +422,43 @@ pub mod pallet {
 			let kitty_1 = Self::kitties(kitty_id_1).ok_or(Error::<T>::InvalidKittyIndex)?;
 			let kitty_2 = Self::kitties(kitty_id_2).ok_or(Error::<T>::InvalidKittyIndex)?;
 
-			let kitty_id = match Self::kitties_count() {
-				None => 1,
-				Some(kitty_id) => kitty_id,
-			};
+			// 拍卖中的小猫不能繁殖
+			Self::ensure_not_in_auction(kitty_id_1)?;
+			Self::ensure_not_in_auction(kitty_id_2)?;
 
-			let dna_1 = kitty_1.dna;
-			let dna_2 = kitty_2.dna;
+			// 确保两只小猫 都休息好了
+			let now = <frame_system::Pallet<T>>::block_number();
+			ensure!(
+				now >= Self::next_breedable_at(kitty_id_1),
+				Error::<T>::BreedCooldownNotExpired
+			);
+			ensure!(
+				now >= Self::next_breedable_at(kitty_id_2),
+				Error::<T>::BreedCooldownNotExpired
+			);
 
-			let selector = Self::gen_dna();
-			let mut new_dna = [0u8; 16];
+			// 确保有权使用两只小猫繁殖，用别人的小猫需要支付配种费
+			Self::pay_for_breeding(&who, kitty_id_1)?;
+			Self::pay_for_breeding(&who, kitty_id_2)?;
 
-			for i in 0..dna_1.len() {
-				new_dna[i] = selector[i] & dna_1[i] | (selector[i] & dna_2[i])
-			}
+			NextBreedableAt::<T>::insert(kitty_id_1, now.saturating_add(Self::cooldown(&kitty_1)));
+			NextBreedableAt::<T>::insert(kitty_id_2, now.saturating_add(Self::cooldown(&kitty_2)));
 
-			Kitties::<T>::insert(kitty_id, Kitty::<T> { dna: new_dna, price: None });
-			Owner::<T>::insert(kitty_id, who.clone());
-			KittiesCount::<T>::put(kitty_id + 1);
+			// 按运行时配置的遗传规则混合父母的DNA
+			let selector = Self::gen_dna();
+			let new_dna = T::DnaMixer::mix(&kitty_1.dna, &kitty_2.dna, &selector);
+
+			// 孩子的代数比父母中代数较大的一方多1
+			let generation = kitty_1.generation.max(kitty_2.generation).saturating_add(1);
+			let kitty_id = Self::mint(&who, new_dna, Some((kitty_id_1, kitty_id_2)), generation)?;
 
-			Self::deposit_event(Event::BreedSuccess(who, kitty_id_1, kitty_id_2));
+			Self::deposit_event(Event::BreedSuccess(who, kitty_id_1, kitty_id_2, kitty_id));
 
 			Ok(().into())
 		}
 
 		/// 给小猫设置价格（卖）
-		#[pallet::weight(0)]
+		#[pallet::weight(T::WeightInfo::set_price())]
 		pub fn set_price(
 			origin: OriginFor<T>,
 			kitty_id: KittyIndex,
@@ -161,6 +477,9 @@ pub mod pallet {
 			// 确保 小猫售价大于0
 			ensure!(price > 0u32.into(), <Error<T>>::PriceNotZero);
 
+			// 拍卖中的小猫不能再挂单出售
+			Self::ensure_not_in_auction(kitty_id)?;
+
 			kitty.price = Some(price);
 			<Kitties<T>>::insert(kitty_id, kitty);
 
@@ -169,10 +488,14 @@ pub mod pallet {
 			Ok(().into())
 		}
 
-		/// 购买小猫
+		/// 购买小猫，`max_price` 是买家愿意支付的最高价格，防止卖家在交易打包前抬价
 		#[transactional]
-		#[pallet::weight(0)]
-		pub fn buy_kitty(origin: OriginFor<T>, kitty_id: KittyIndex) -> DispatchResult {
+		#[pallet::weight(T::WeightInfo::buy_kitty())]
+		pub fn buy_kitty(
+			origin: OriginFor<T>,
+			kitty_id: KittyIndex,
+			max_price: BalanceOf<T>,
+		) -> DispatchResult {
 			let buyer = ensure_signed(origin)?;
 
 			// 判断小猫是否存在
@@ -180,6 +503,8 @@ pub mod pallet {
 
 			// 判断小猫是否有售价
 			if let Some(price) = kitty.price {
+				// 判断售价没有超过买家愿意支付的价格
+				ensure!(price <= max_price, <Error<T>>::PriceTooHigh);
 				// 判断买家是否有足够的钱
 				ensure!(T::Currency::free_balance(&buyer) >= price, <Error<T>>::MoneyNotEnough);
 			} else {
@@ -189,33 +514,705 @@ pub mod pallet {
 			// 获得卖家ID
 			let seller_id = <Owner<T>>::get(&kitty_id).unwrap();
 
-			// 开始转账
-			T::Currency::transfer(
-				&buyer,
-				&seller_id,
-				kitty.price.unwrap(),
-				ExistenceRequirement::KeepAlive,
-			)?;
+			// 不能购买自己的小猫
+			ensure!(buyer != seller_id, <Error<T>>::CanNotYourSelf);
+
+			// 开始转账，扣除市场手续费和版税
+			let price = kitty.price.unwrap();
+			let (fee, royalty) =
+				Self::pay_for_sale(&buyer, &seller_id, kitty.creator.as_ref(), price)?;
 
 			// 更改小猫的主人
-			<Owner<T>>::insert(&kitty_id, &buyer);
+			Self::change_owner(&seller_id, &buyer, kitty_id)?;
 
 			// 小猫售价设置为None
 			kitty.price = None;
 			<Kitties<T>>::insert(&kitty_id, kitty);
 
-			Self::deposit_event(Event::TransferSuccess(buyer.clone(), seller_id.clone(), kitty_id));
+			Self::deposit_event(Event::KittySold(buyer, seller_id, kitty_id, price, fee, royalty));
+
+			Ok(().into())
+		}
+
+		/// 转让小猫
+		#[transactional]
+		#[pallet::weight(T::WeightInfo::transfer())]
+		pub fn transfer(
+			origin: OriginFor<T>,
+			to: T::AccountId,
+			kitty_id: KittyIndex,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+
+			Self::do_transfer(&sender, to, kitty_id)?;
+
+			Ok(().into())
+		}
+
+		/// 授权别人用自己的小猫繁殖
+		#[pallet::weight(T::WeightInfo::offer_sire())]
+		pub fn offer_sire(
+			origin: OriginFor<T>,
+			kitty_id: KittyIndex,
+			breeder: Option<T::AccountId>,
+			fee: BalanceOf<T>,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+
+			// 判断这只猫是否属于此人
+			let owner = Self::owner(&kitty_id).ok_or(<Error<T>>::InvalidKittyIndex)?;
+			ensure!(owner == sender, <Error<T>>::NotOwner);
+
+			// 不能授权给自己
+			ensure!(breeder.as_ref() != Some(&sender), <Error<T>>::CanNotYourSelf);
+
+			<SireOffers<T>>::insert(kitty_id, SireOffer::<T> { breeder: breeder.clone(), fee });
+
+			Self::deposit_event(Event::SireOfferSet(sender, kitty_id, breeder, fee));
+
+			Ok(().into())
+		}
+
+		/// 取消配种授权
+		#[pallet::weight(T::WeightInfo::cancel_sire_offer())]
+		pub fn cancel_sire_offer(origin: OriginFor<T>, kitty_id: KittyIndex) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+
+			// 判断这只猫是否属于此人
+			let owner = Self::owner(&kitty_id).ok_or(<Error<T>>::InvalidKittyIndex)?;
+			ensure!(owner == sender, <Error<T>>::NotOwner);
+
+			ensure!(<SireOffers<T>>::contains_key(kitty_id), <Error<T>>::SireOfferNotExist);
+			<SireOffers<T>>::remove(kitty_id);
+
+			Self::deposit_event(Event::SireOfferCancelled(sender, kitty_id));
+
+			Ok(().into())
+		}
+
+		/// 拍卖小猫，在 `end_block` 开始时自动结算。拍卖不能取消，最长持续 `MaxAuctionDuration`
+		#[pallet::weight(T::WeightInfo::create_auction())]
+		pub fn create_auction(
+			origin: OriginFor<T>,
+			kitty_id: KittyIndex,
+			reserve: BalanceOf<T>,
+			end_block: T::BlockNumber,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+
+			// 检查这只猫是否真实存在
+			let mut kitty = Self::kitties(&kitty_id).ok_or(<Error<T>>::InvalidKittyIndex)?;
+
+			// 判断这只猫是否属于此人
+			ensure!(Self::owner(&kitty_id) == Some(sender.clone()), <Error<T>>::NotOwner);
+
+			Self::ensure_not_in_auction(kitty_id)?;
+
+			let now = <frame_system::Pallet<T>>::block_number();
+			ensure!(end_block > now, <Error<T>>::InvalidAuctionEnd);
+			ensure!(
+				end_block <= now.saturating_add(T::MaxAuctionDuration::get()),
+				<Error<T>>::AuctionDurationTooLong
+			);
+
+			<AuctionsEndingAt<T>>::try_mutate(end_block, |ending| {
+				ending.try_push(kitty_id).map_err(|_| <Error<T>>::TooManyAuctionsEnding)
+			})?;
+
+			// 拍卖期间不能按固定价格购买
+			if kitty.price.is_some() {
+				kitty.price = None;
+				<Kitties<T>>::insert(kitty_id, kitty);
+			}
+
+			<Auctions<T>>::insert(
+				kitty_id,
+				Auction::<T> { seller: sender.clone(), reserve, end_block, top_bid: None },
+			);
+
+			Self::deposit_event(Event::AuctionCreated(sender, kitty_id, reserve, end_block));
+
+			Ok(().into())
+		}
+
+		/// 出价，出价金额会被锁定，直到被更高的出价超过或拍卖结束
+		#[transactional]
+		#[pallet::weight(T::WeightInfo::bid())]
+		pub fn bid(
+			origin: OriginFor<T>,
+			kitty_id: KittyIndex,
+			amount: BalanceOf<T>,
+		) -> DispatchResult {
+			let bidder = ensure_signed(origin)?;
+
+			let mut auction = Self::auctions(kitty_id).ok_or(<Error<T>>::AuctionNotExist)?;
+
+			let now = <frame_system::Pallet<T>>::block_number();
+			ensure!(now < auction.end_block, <Error<T>>::AuctionEnded);
+
+			// 卖家不能给自己出价
+			ensure!(bidder != auction.seller, <Error<T>>::CanNotYourSelf);
+
+			ensure!(amount >= auction.reserve, <Error<T>>::BidTooLow);
+			if let Some((previous_bidder, previous_amount)) = &auction.top_bid {
+				ensure!(amount > *previous_amount, <Error<T>>::BidTooLow);
+				// 退还上一个最高出价
+				T::Currency::unreserve(previous_bidder, *previous_amount);
+			}
+
+			T::Currency::reserve(&bidder, amount).map_err(|_| <Error<T>>::MoneyNotEnough)?;
+
+			auction.top_bid = Some((bidder.clone(), amount));
+			<Auctions<T>>::insert(kitty_id, auction);
+
+			Self::deposit_event(Event::BidPlaced(bidder, kitty_id, amount));
+
+			Ok(().into())
+		}
+
+		/// 取消出售小猫
+		#[pallet::weight(T::WeightInfo::unlist())]
+		pub fn unlist(origin: OriginFor<T>, kitty_id: KittyIndex) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+
+			// 检查这只猫是否真实存在
+			let mut kitty = Self::kitties(&kitty_id).ok_or(<Error<T>>::InvalidKittyIndex)?;
+
+			// 判断这只猫是否属于此人
+			ensure!(Self::owner(&kitty_id) == Some(sender.clone()), <Error<T>>::NotOwner);
+
+			// 只能取消已经挂单的小猫
+			ensure!(kitty.price.is_some(), <Error<T>>::PriceIsNone);
+
+			kitty.price = None;
+			<Kitties<T>>::insert(kitty_id, kitty);
+
+			Self::deposit_event(Event::KittyUnlisted(sender, kitty_id));
+
+			Ok(().into())
+		}
+
+		/// 销毁小猫，退还押金
+		#[transactional]
+		#[pallet::weight(T::WeightInfo::burn())]
+		pub fn burn(origin: OriginFor<T>, kitty_id: KittyIndex) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+
+			// 判断这只猫是否属于此人
+			let owner = Self::owner(&kitty_id).ok_or(<Error<T>>::InvalidKittyIndex)?;
+			ensure!(owner == sender, <Error<T>>::NotOwner);
+
+			// 拍卖中的小猫已经有人出价，不能销毁
+			Self::ensure_not_in_auction(kitty_id)?;
+
+			Self::remove_kitty_from_owner(&sender, kitty_id);
+			T::Currency::unreserve(&sender, <KittyDeposits<T>>::take(kitty_id));
+
+			// 删除小猫的所有记录，小猫索引不会被重新使用
+			<Kitties<T>>::remove(kitty_id);
+			<Owner<T>>::remove(kitty_id);
+			<SireOffers<T>>::remove(kitty_id);
+			<NextBreedableAt<T>>::remove(kitty_id);
+
+			Self::deposit_event(Event::KittyBurned(sender, kitty_id));
+
+			Ok(().into())
+		}
+
+		/// 对小猫报价，不需要小猫挂单出售。报价金额会被锁定，直到报价被接受、撤回或失效
+		#[transactional]
+		#[pallet::weight(T::WeightInfo::make_offer())]
+		pub fn make_offer(
+			origin: OriginFor<T>,
+			kitty_id: KittyIndex,
+			amount: BalanceOf<T>,
+			expires_at: T::BlockNumber,
+		) -> DispatchResult {
+			let offerer = ensure_signed(origin)?;
+
+			// 不能给自己的小猫报价
+			let owner = Self::owner(&kitty_id).ok_or(<Error<T>>::InvalidKittyIndex)?;
+			ensure!(owner != offerer, <Error<T>>::CanNotYourSelf);
+
+			ensure!(amount > 0u32.into(), <Error<T>>::PriceNotZero);
+			ensure!(!<Offers<T>>::contains_key(kitty_id, &offerer), <Error<T>>::OfferAlreadyExists);
+
+			let now = <frame_system::Pallet<T>>::block_number();
+			ensure!(expires_at > now, <Error<T>>::InvalidOfferExpiry);
+			ensure!(
+				expires_at <= now.saturating_add(T::MaxOfferDuration::get()),
+				<Error<T>>::OfferDurationTooLong
+			);
+
+			<OffersExpiringAt<T>>::try_mutate(expires_at, |expiring| {
+				expiring
+					.try_push((kitty_id, offerer.clone()))
+					.map_err(|_| <Error<T>>::TooManyOffersExpiring)
+			})?;
+
+			T::Currency::reserve(&offerer, amount).map_err(|_| <Error<T>>::MoneyNotEnough)?;
+
+			<Offers<T>>::insert(kitty_id, &offerer, Offer::<T> { amount, expires_at });
+
+			// 清理进度只在第一次报价时初始化，之后由 on_idle 推进
+			if Self::next_offer_cleanup().is_none() {
+				<NextOfferCleanup<T>>::put(now);
+			}
+
+			Self::deposit_event(Event::OfferMade(offerer, kitty_id, amount, expires_at));
+
+			Ok(().into())
+		}
+
+		/// 主人接受报价: 小猫交给报价人，锁定的金额扣除手续费和版税后付给主人
+		#[transactional]
+		#[pallet::weight(T::WeightInfo::accept_offer())]
+		pub fn accept_offer(
+			origin: OriginFor<T>,
+			kitty_id: KittyIndex,
+			offerer: T::AccountId,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+
+			// 检查这只猫是否真实存在
+			let mut kitty = Self::kitties(&kitty_id).ok_or(<Error<T>>::InvalidKittyIndex)?;
+
+			// 判断这只猫是否属于此人
+			ensure!(Self::owner(&kitty_id) == Some(sender.clone()), <Error<T>>::NotOwner);
+
+			// 报价人买到小猫后，之前的报价只能撤回，不能接受
+			ensure!(offerer != sender, <Error<T>>::CanNotYourSelf);
+
+			// 拍卖中的小猫不能卖给别人
+			Self::ensure_not_in_auction(kitty_id)?;
+
+			let offer = Self::offers(kitty_id, &offerer).ok_or(<Error<T>>::OfferNotExist)?;
+			let now = <frame_system::Pallet<T>>::block_number();
+			ensure!(now < offer.expires_at, <Error<T>>::OfferExpired);
+
+			Self::remove_offer(kitty_id, &offerer, &offer);
+
+			let (fee, royalty) =
+				Self::pay_for_sale(&offerer, &sender, kitty.creator.as_ref(), offer.amount)?;
+
+			// 更改小猫的主人
+			Self::change_owner(&sender, &offerer, kitty_id)?;
+
+			// 卖出的小猫不再挂单出售
+			if kitty.price.is_some() {
+				kitty.price = None;
+				<Kitties<T>>::insert(kitty_id, kitty);
+			}
+
+			Self::deposit_event(Event::OfferAccepted(
+				sender,
+				offerer,
+				kitty_id,
+				offer.amount,
+				fee,
+				royalty,
+			));
+
+			Ok(().into())
+		}
+
+		/// 撤回报价，退还锁定的金额
+		#[pallet::weight(T::WeightInfo::withdraw_offer())]
+		pub fn withdraw_offer(origin: OriginFor<T>, kitty_id: KittyIndex) -> DispatchResult {
+			let offerer = ensure_signed(origin)?;
+
+			let offer = Self::offers(kitty_id, &offerer).ok_or(<Error<T>>::OfferNotExist)?;
+
+			Self::remove_offer(kitty_id, &offerer, &offer);
+
+			Self::deposit_event(Event::OfferWithdrawn(offerer, kitty_id));
+
+			Ok(().into())
+		}
+
+		/// 批量创建小猫，全部成功或者全部失败
+		#[transactional]
+		#[pallet::weight(T::WeightInfo::create_batch(*count))]
+		pub fn create_batch(origin: OriginFor<T>, count: u32) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			ensure!(count > 0, <Error<T>>::EmptyBatch);
+			ensure!(count <= T::MaxBatchSize::get(), <Error<T>>::BatchTooLarge);
+
+			// 新小猫的索引从当前的 KittiesCount 开始连续分配
+			let first_id = Self::kitties_count().unwrap_or(1);
+			for dna in Self::gen_dna_batch(count) {
+				let kitty_id = Self::mint(&who, dna, None, 0)?;
+
+				Self::deposit_event(Event::KittyCreate(who.clone(), kitty_id));
+			}
+
+			Self::deposit_event(Event::BatchCreated(who, first_id, count));
+
+			Ok(().into())
+		}
+
+		/// 批量转让小猫: [(接收人, 小猫)]，全部成功或者全部失败
+		#[transactional]
+		#[pallet::weight(T::WeightInfo::transfer_batch(transfers.len() as u32))]
+		pub fn transfer_batch(
+			origin: OriginFor<T>,
+			transfers: Vec<(T::AccountId, KittyIndex)>,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+
+			ensure!(!transfers.is_empty(), <Error<T>>::EmptyBatch);
+			ensure!(transfers.len() as u32 <= T::MaxBatchSize::get(), <Error<T>>::BatchTooLarge);
+
+			let count = transfers.len() as u32;
+			for (to, kitty_id) in transfers {
+				Self::do_transfer(&sender, to, kitty_id)?;
+			}
+
+			Self::deposit_event(Event::BatchTransferred(sender, count));
 
 			Ok(().into())
 		}
 	}
 
 	impl<T: Config> Pallet<T> {
+		/// 某个账户拥有的小猫，第 `page` 页（从0开始），供 RPC 查询
+		pub fn owned_kitties_page(
+			owner: &T::AccountId,
+			page: u32,
+			page_size: u32,
+		) -> Vec<KittyIndex> {
+			let start = page.saturating_mul(page_size) as usize;
+			Self::owned_kitties(owner)
+				.into_iter()
+				.skip(start)
+				.take(page_size as usize)
+				.collect()
+		}
+
+		/// 正在出售的小猫，第 `page` 页（从0开始），供 RPC 查询。
+		/// 需要遍历所有小猫，顺序由存储键的哈希决定，只在链上状态不变时稳定
+		pub fn listed_kitties_page(page: u32, page_size: u32) -> Vec<KittyIndex> {
+			let start = page.saturating_mul(page_size) as usize;
+			<Kitties<T>>::iter()
+				.filter(|(_, kitty)| kitty.price.is_some())
+				.map(|(kitty_id, _)| kitty_id)
+				.skip(start)
+				.take(page_size as usize)
+				.collect()
+		}
+
+		/// 用当前基因布局解码小猫的外观
+		pub fn phenotype(kitty_id: KittyIndex) -> Option<Phenotype> {
+			Self::kitties(kitty_id).map(|kitty| decode_phenotype(&kitty.dna))
+		}
+
 		/// 随机生成小猫DNA算法
 		fn gen_dna() -> [u8; 16] {
 			let payload =
 				(T::Randomness::random(&b"dna"[..]).0, <frame_system::Pallet<T>>::block_number());
 			payload.using_encoded(blake2_128)
 		}
+
+		/// 批量生成小猫DNA，只取一次随机数，用序号区分每只小猫
+		fn gen_dna_batch(count: u32) -> Vec<[u8; 16]> {
+			let random = T::Randomness::random(&b"dna"[..]).0;
+			let block_number = <frame_system::Pallet<T>>::block_number();
+			(0..count)
+				.map(|i| (random, block_number, i).using_encoded(blake2_128))
+				.collect()
+		}
+
+		/// 把小猫送给别人，送出去的小猫不再挂单出售
+		fn do_transfer(
+			sender: &T::AccountId,
+			to: T::AccountId,
+			kitty_id: KittyIndex,
+		) -> DispatchResult {
+			// 检查这只猫是否真实存在
+			let mut kitty = Self::kitties(&kitty_id).ok_or(<Error<T>>::InvalidKittyIndex)?;
+
+			// 判断这只猫是否属于此人
+			ensure!(Self::owner(&kitty_id).as_ref() == Some(sender), <Error<T>>::NotOwner);
+
+			// 不能转让给自己
+			ensure!(sender != &to, <Error<T>>::CanNotYourSelf);
+
+			// 拍卖中的小猫不能转让
+			Self::ensure_not_in_auction(kitty_id)?;
+
+			// 更改小猫的主人
+			Self::change_owner(sender, &to, kitty_id)?;
+
+			kitty.price = None;
+			<Kitties<T>>::insert(&kitty_id, kitty);
+
+			Self::deposit_event(Event::Transfer(sender.clone(), kitty_id, to));
+
+			Ok(())
+		}
+
+		/// 铸造一只新小猫并记录主人，主人需要质押押金
+		fn mint(
+			owner: &T::AccountId,
+			dna: [u8; 16],
+			parents: Option<(KittyIndex, KittyIndex)>,
+			generation: u32,
+		) -> Result<KittyIndex, DispatchError> {
+			// 获得 当前小猫id
+			let kitty_id = match Self::kitties_count() {
+				None => 1,
+				Some(index) => {
+					ensure!(index != KittyIndex::max_value(), Error::<T>::KittiesCountOverflow);
+					index
+				},
+			};
+
+			Self::add_kitty_to_owner(owner, kitty_id)?;
+
+			let deposit = T::KittyReserve::get();
+			T::Currency::reserve(owner, deposit)
+				.map_err(|_| Error::<T>::NotEnoughBalanceForReserve)?;
+			KittyDeposits::<T>::insert(kitty_id, deposit);
+
+			let birth_block = <frame_system::Pallet<T>>::block_number();
+			Kitties::<T>::insert(
+				kitty_id,
+				Kitty::<T> {
+					dna,
+					price: None,
+					parents,
+					generation,
+					birth_block,
+					creator: Some(owner.clone()),
+				},
+			);
+			Owner::<T>::insert(kitty_id, owner.clone());
+			KittiesCount::<T>::put(kitty_id + 1);
+
+			Ok(kitty_id)
+		}
+
+		/// 更改小猫的主人，同时更新双方的小猫列表，押金改由新主人质押
+		fn change_owner(
+			from: &T::AccountId,
+			to: &T::AccountId,
+			kitty_id: KittyIndex,
+		) -> DispatchResult {
+			// 主人不变时先加后删会把小猫从 OwnedKitties 中删掉
+			ensure!(from != to, <Error<T>>::CanNotYourSelf);
+
+			Self::add_kitty_to_owner(to, kitty_id)?;
+			Self::remove_kitty_from_owner(from, kitty_id);
+
+			// 新主人按当前的 KittyReserve 质押，原主人取回当初质押的金额
+			let deposit = T::KittyReserve::get();
+			T::Currency::reserve(to, deposit)
+				.map_err(|_| Error::<T>::NotEnoughBalanceForReserve)?;
+			T::Currency::unreserve(from, <KittyDeposits<T>>::get(kitty_id));
+			<KittyDeposits<T>>::insert(kitty_id, deposit);
+
+			<Owner<T>>::insert(kitty_id, to);
+
+			// 配种授权属于原主人，换主人后失效
+			<SireOffers<T>>::remove(kitty_id);
+
+			Ok(())
+		}
+
+		/// 删除报价和失效索引，并退还锁定的金额
+		fn remove_offer(kitty_id: KittyIndex, offerer: &T::AccountId, offer: &Offer<T>) {
+			<Offers<T>>::remove(kitty_id, offerer);
+			<OffersExpiringAt<T>>::mutate_exists(offer.expires_at, |maybe_expiring| {
+				if let Some(expiring) = maybe_expiring {
+					expiring.retain(|(id, who)| !(*id == kitty_id && who == offerer));
+					if expiring.is_empty() {
+						*maybe_expiring = None;
+					}
+				}
+			});
+			T::Currency::unreserve(offerer, offer.amount);
+		}
+
+		/// 从上次清理到的区块开始，逐个区块清理失效的报价，直到 `now` 或者权重用完
+		fn expire_offers(now: T::BlockNumber, limit: Weight) -> Weight {
+			let db_weight = T::DbWeight::get();
+			// 读写清理进度
+			let mut used = db_weight.reads_writes(1, 1);
+			if used > limit {
+				return 0
+			}
+
+			// 还没有人报价过，没有需要清理的报价
+			let mut block = match Self::next_offer_cleanup() {
+				Some(block) => block,
+				None => return db_weight.reads(1),
+			};
+			while block <= now {
+				let expiring = <OffersExpiringAt<T>>::get(block);
+				let cost = T::WeightInfo::expire_offers(expiring.len() as u32);
+				if used.saturating_add(cost) > limit {
+					// 读取了失效索引但没有处理
+					used = used.saturating_add(db_weight.reads(1));
+					break
+				}
+				used = used.saturating_add(cost);
+
+				<OffersExpiringAt<T>>::remove(block);
+				for (kitty_id, offerer) in expiring.into_iter() {
+					if let Some(offer) = <Offers<T>>::take(kitty_id, &offerer) {
+						T::Currency::unreserve(&offerer, offer.amount);
+						Self::deposit_event(Event::OfferExpired(offerer, kitty_id));
+					}
+				}
+
+				block = block.saturating_add(1u32.into());
+			}
+
+			<NextOfferCleanup<T>>::put(block);
+
+			used
+		}
+
+		/// 确保小猫没有在拍卖
+		fn ensure_not_in_auction(kitty_id: KittyIndex) -> DispatchResult {
+			ensure!(!<Auctions<T>>::contains_key(kitty_id), Error::<T>::KittyInAuction);
+			Ok(())
+		}
+
+		/// 结算拍卖: 最高出价人付款并得到小猫；没有出价或者无法完成交割时流拍，退还出价
+		fn settle_auction(kitty_id: KittyIndex) {
+			let auction = match <Auctions<T>>::take(kitty_id) {
+				Some(auction) => auction,
+				None => return,
+			};
+
+			if let Some((winner, amount)) = auction.top_bid {
+				// 交割失败（比如买家的小猫已经达到上限）时回滚所有修改
+				let sold = with_transaction(|| {
+					match Self::deliver_auction(&auction.seller, &winner, kitty_id, amount) {
+						Ok(paid) => TransactionOutcome::Commit(Some(paid)),
+						Err(_) => TransactionOutcome::Rollback(None),
+					}
+				});
+
+				if let Some((fee, royalty)) = sold {
+					Self::deposit_event(Event::AuctionWon(
+						auction.seller,
+						winner,
+						kitty_id,
+						amount,
+						fee,
+						royalty,
+					));
+					return
+				}
+
+				T::Currency::unreserve(&winner, amount);
+			}
+
+			Self::deposit_event(Event::AuctionUnsold(auction.seller, kitty_id));
+		}
+
+		/// 把锁定的出价付给卖家，并把小猫交给买家，返回 (市场手续费, 版税)
+		fn deliver_auction(
+			seller: &T::AccountId,
+			winner: &T::AccountId,
+			kitty_id: KittyIndex,
+			amount: BalanceOf<T>,
+		) -> Result<(BalanceOf<T>, BalanceOf<T>), DispatchError> {
+			let kitty = Self::kitties(kitty_id).ok_or(Error::<T>::InvalidKittyIndex)?;
+
+			let remaining = T::Currency::unreserve(winner, amount);
+			ensure!(remaining.is_zero(), Error::<T>::MoneyNotEnough);
+			let paid = Self::pay_for_sale(winner, seller, kitty.creator.as_ref(), amount)?;
+
+			Self::change_owner(seller, winner, kitty_id)?;
+
+			Ok(paid)
+		}
+
+		/// 买家支付售价: 市场手续费交给 `OnMarketplaceFee`，版税付给创作者，剩下的付给卖家。
+		/// 返回 (市场手续费, 版税)
+		fn pay_for_sale(
+			buyer: &T::AccountId,
+			seller: &T::AccountId,
+			creator: Option<&T::AccountId>,
+			price: BalanceOf<T>,
+		) -> Result<(BalanceOf<T>, BalanceOf<T>), DispatchError> {
+			let fee = T::MarketplaceFee::get() * price;
+			if !fee.is_zero() {
+				let imbalance = T::Currency::withdraw(
+					buyer,
+					fee,
+					WithdrawReasons::TRANSFER,
+					ExistenceRequirement::KeepAlive,
+				)?;
+				T::OnMarketplaceFee::on_unbalanced(imbalance);
+			}
+
+			// 创作者自己卖出时不收版税；手续费和版税加起来不会超过售价
+			let mut royalty: BalanceOf<T> = Zero::zero();
+			if let Some(creator) = creator.filter(|creator| *creator != seller) {
+				royalty = (T::CreatorRoyalty::get() * price).min(price.saturating_sub(fee));
+				if !royalty.is_zero() {
+					T::Currency::transfer(
+						buyer,
+						creator,
+						royalty,
+						ExistenceRequirement::KeepAlive,
+					)?;
+				}
+			}
+
+			let proceeds = price.saturating_sub(fee).saturating_sub(royalty);
+			T::Currency::transfer(buyer, seller, proceeds, ExistenceRequirement::KeepAlive)?;
+
+			Ok((fee, royalty))
+		}
+
+		/// 繁殖后的休息时间: BreedCooldown * (代数 + 1)
+		fn cooldown(kitty: &Kitty<T>) -> T::BlockNumber {
+			T::BreedCooldown::get().saturating_mul(kitty.generation.saturating_add(1).into())
+		}
+
+		/// 检查繁殖权限: 自己的小猫直接使用，别人的小猫需要配种授权并支付配种费
+		fn pay_for_breeding(who: &T::AccountId, kitty_id: KittyIndex) -> DispatchResult {
+			let owner = Self::owner(kitty_id).ok_or(Error::<T>::InvalidKittyIndex)?;
+			if &owner == who {
+				return Ok(())
+			}
+
+			let offer = Self::sire_offers(kitty_id).ok_or(Error::<T>::NotAllowedToBreed)?;
+			if let Some(breeder) = &offer.breeder {
+				ensure!(breeder == who, Error::<T>::NotAllowedToBreed);
+			}
+
+			if !offer.fee.is_zero() {
+				T::Currency::transfer(who, &owner, offer.fee, ExistenceRequirement::KeepAlive)?;
+				Self::deposit_event(Event::SireFeePaid(who.clone(), owner, kitty_id, offer.fee));
+			}
+
+			Ok(())
+		}
+
+		/// 把小猫加入主人的列表
+		fn add_kitty_to_owner(owner: &T::AccountId, kitty_id: KittyIndex) -> DispatchResult {
+			<OwnedKitties<T>>::try_mutate(owner, |kitties| {
+				kitties.try_push(kitty_id).map_err(|_| Error::<T>::ExceedMaxKittiesOwned.into())
+			})
+		}
+
+		/// 把小猫从主人的列表中移除，列表为空时删除该项
+		fn remove_kitty_from_owner(owner: &T::AccountId, kitty_id: KittyIndex) {
+			<OwnedKitties<T>>::mutate_exists(owner, |maybe_kitties| {
+				if let Some(kitties) = maybe_kitties {
+					kitties.retain(|id| *id != kitty_id);
+					if kitties.is_empty() {
+						*maybe_kitties = None;
+					}
+				}
+			});
+		}
 	}
 }
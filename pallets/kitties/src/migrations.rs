@@ -0,0 +1,283 @@
+//! 存储迁移
+//!
+//! 每次修改 `Kitty` 等存储的编码格式时:
+//! 1. 提升 `lib.rs` 中的 `STORAGE_VERSION`
+//! 2. 新增一个 `vN` 模块，实现 `migrate`，以及 `try-runtime` 下的 `pre_upgrade` / `post_upgrade`
+//! 3. 在下面的 `migrate` / `pre_upgrade` / `post_upgrade` 中按版本顺序追加调用
+//!
+//! 每个 `vN::migrate` 只在链上版本为 `N - 1` 时执行，因此依次调用是安全的。
+
+use crate::Config;
+use frame_support::weights::Weight;
+
+/// 依次执行所有迁移，已经完成的迁移只消耗一次读取
+pub fn migrate<T: Config>() -> Weight {
+	let mut weight: Weight = 0;
+	weight = weight.saturating_add(v1::migrate::<T>());
+	weight = weight.saturating_add(v2::migrate::<T>());
+	weight = weight.saturating_add(v3::migrate::<T>());
+	weight
+}
+
+/// 升级前检查，只用于 try-runtime
+#[cfg(feature = "try-runtime")]
+pub fn pre_upgrade<T: Config>() -> Result<(), &'static str> {
+	v1::pre_upgrade::<T>()?;
+	v2::pre_upgrade::<T>()?;
+	v3::pre_upgrade::<T>()
+}
+
+/// 升级后检查，只用于 try-runtime
+#[cfg(feature = "try-runtime")]
+pub fn post_upgrade<T: Config>() -> Result<(), &'static str> {
+	v1::post_upgrade::<T>()?;
+	v2::post_upgrade::<T>()?;
+	v3::post_upgrade::<T>()
+}
+
+/// v0 -> v1: 小猫增加 `parents`、`generation` 和 `birth_block`
+pub mod v1 {
+	use super::v2::OldKitty as KittyV1;
+	use crate::{BalanceOf, Config, Kitties, Pallet};
+	use codec::{Decode, Encode};
+	use frame_support::{
+		storage::unhashed,
+		traits::{Get, GetStorageVersion, StorageVersion},
+		weights::Weight,
+	};
+	use sp_runtime::traits::Zero;
+	use sp_std::vec::Vec;
+
+	/// v0 版本的小猫，只有 dna 和售价
+	#[derive(Decode, Encode)]
+	pub struct OldKitty<Balance> {
+		pub dna: [u8; 16],
+		pub price: Option<Balance>,
+	}
+
+	/// 已有的小猫都当作第0代，父母和出生区块未知
+	pub fn migrate<T: Config>() -> Weight {
+		if Pallet::<T>::on_chain_storage_version() != 0 {
+			return T::DbWeight::get().reads(1)
+		}
+
+		// `Kitties` 的类型已经是最新版本，这里直接按 v1 的格式读写原始存储
+		let mut translated = 0u64;
+		let kitty_ids = Kitties::<T>::iter_keys().collect::<Vec<_>>();
+		for kitty_id in kitty_ids {
+			let key = Kitties::<T>::hashed_key_for(kitty_id);
+			translated += 1;
+			match unhashed::get::<OldKitty<BalanceOf<T>>>(&key) {
+				Some(old) => unhashed::put(
+					&key,
+					&KittyV1::<BalanceOf<T>, T::BlockNumber> {
+						dna: old.dna,
+						price: old.price,
+						parents: None,
+						generation: 0,
+						birth_block: Zero::zero(),
+					},
+				),
+				// 和 translate 一样，删除无法解码的值
+				None => unhashed::kill(&key),
+			}
+		}
+
+		StorageVersion::new(1).put::<Pallet<T>>();
+
+		T::DbWeight::get().reads_writes(translated + 1, translated + 1)
+	}
+
+	/// 检查所有已存储的小猫都能按 v0 格式解码，并记下数量
+	#[cfg(feature = "try-runtime")]
+	pub fn pre_upgrade<T: Config>() -> Result<(), &'static str> {
+		use frame_support::traits::OnRuntimeUpgradeHelpersExt;
+
+		if Pallet::<T>::on_chain_storage_version() != 0 {
+			return Ok(())
+		}
+
+		let mut count = 0u32;
+		for kitty_id in Kitties::<T>::iter_keys() {
+			let key = Kitties::<T>::hashed_key_for(kitty_id);
+			unhashed::get::<OldKitty<BalanceOf<T>>>(&key).ok_or("v1: kitty is not in v0 format")?;
+			count += 1;
+		}
+		Pallet::<T>::set_temp_storage(count, "v1_kitties");
+
+		Ok(())
+	}
+
+	/// 检查版本已经是 1，并且迁移前的小猫都能按新格式解码
+	#[cfg(feature = "try-runtime")]
+	pub fn post_upgrade<T: Config>() -> Result<(), &'static str> {
+		use frame_support::traits::OnRuntimeUpgradeHelpersExt;
+
+		if Pallet::<T>::on_chain_storage_version() < 1 {
+			return Err("v1: storage version was not updated")
+		}
+
+		if let Some(count) = Pallet::<T>::get_temp_storage::<u32>("v1_kitties") {
+			// 无法解码的值会被 iter_values 跳过
+			if Kitties::<T>::iter_values().count() as u32 != count {
+				return Err("v1: some kitties were lost during migration")
+			}
+		}
+
+		Ok(())
+	}
+}
+
+/// v1 -> v2: 小猫增加 `creator`，用于支付版税
+pub mod v2 {
+	use crate::{BalanceOf, Config, Kitties, Kitty, KittyIndex, Pallet};
+	use codec::{Decode, Encode};
+	use frame_support::{
+		traits::{Get, GetStorageVersion, StorageVersion},
+		weights::Weight,
+	};
+
+	/// v1 版本的小猫，没有记录创作者
+	#[derive(Decode, Encode)]
+	pub struct OldKitty<Balance, BlockNumber> {
+		pub dna: [u8; 16],
+		pub price: Option<Balance>,
+		pub parents: Option<(KittyIndex, KittyIndex)>,
+		pub generation: u32,
+		pub birth_block: BlockNumber,
+	}
+
+	/// 已有小猫的创作者未知，不支付版税
+	pub fn migrate<T: Config>() -> Weight {
+		if Pallet::<T>::on_chain_storage_version() != 1 {
+			return T::DbWeight::get().reads(1)
+		}
+
+		let mut translated = 0u64;
+		Kitties::<T>::translate::<OldKitty<BalanceOf<T>, T::BlockNumber>, _>(|_, old| {
+			translated += 1;
+			Some(Kitty::<T> {
+				dna: old.dna,
+				price: old.price,
+				parents: old.parents,
+				generation: old.generation,
+				birth_block: old.birth_block,
+				creator: None,
+			})
+		});
+
+		StorageVersion::new(2).put::<Pallet<T>>();
+
+		T::DbWeight::get().reads_writes(translated + 1, translated + 1)
+	}
+
+	/// 检查所有已存储的小猫都能按 v1 格式解码，并记下数量
+	///
+	/// 链上版本为 0 时 v1 还没有执行，由 `v1::pre_upgrade` 负责检查
+	#[cfg(feature = "try-runtime")]
+	pub fn pre_upgrade<T: Config>() -> Result<(), &'static str> {
+		use frame_support::{storage::unhashed, traits::OnRuntimeUpgradeHelpersExt};
+
+		if Pallet::<T>::on_chain_storage_version() != 1 {
+			return Ok(())
+		}
+
+		let mut count = 0u32;
+		for kitty_id in Kitties::<T>::iter_keys() {
+			let key = Kitties::<T>::hashed_key_for(kitty_id);
+			unhashed::get::<OldKitty<BalanceOf<T>, T::BlockNumber>>(&key)
+				.ok_or("v2: kitty is not in v1 format")?;
+			count += 1;
+		}
+		Pallet::<T>::set_temp_storage(count, "v2_kitties");
+
+		Ok(())
+	}
+
+	/// 检查版本已经是 2，并且迁移前的小猫都能按新格式解码
+	#[cfg(feature = "try-runtime")]
+	pub fn post_upgrade<T: Config>() -> Result<(), &'static str> {
+		use frame_support::traits::OnRuntimeUpgradeHelpersExt;
+
+		if Pallet::<T>::on_chain_storage_version() < 2 {
+			return Err("v2: storage version was not updated")
+		}
+
+		if let Some(count) = Pallet::<T>::get_temp_storage::<u32>("v2_kitties") {
+			if Kitties::<T>::iter_values().count() as u32 != count {
+				return Err("v2: some kitties were lost during migration")
+			}
+		}
+
+		Ok(())
+	}
+}
+
+/// v2 -> v3: 按 `Owner` 补全 `OwnedKitties`
+///
+/// `OwnedKitties` 加入之前铸造的小猫不在主人的列表中。已经在列表中的小猫不会重复加入，
+/// 超过 `MaxKittiesOwned` 的小猫无法加入列表，仍然属于原来的主人
+pub mod v3 {
+	use crate::{Config, OwnedKitties, Owner, Pallet};
+	use frame_support::{
+		traits::{Get, GetStorageVersion, StorageVersion},
+		weights::Weight,
+	};
+
+	/// 把每只小猫加入主人的列表
+	pub fn migrate<T: Config>() -> Weight {
+		if Pallet::<T>::on_chain_storage_version() != 2 {
+			return T::DbWeight::get().reads(1)
+		}
+
+		let mut indexed = 0u64;
+		for (kitty_id, owner) in Owner::<T>::iter() {
+			indexed += 1;
+			OwnedKitties::<T>::mutate(&owner, |kitties| {
+				if !kitties.contains(&kitty_id) {
+					let _ = kitties.try_push(kitty_id);
+				}
+			});
+		}
+
+		StorageVersion::new(3).put::<Pallet<T>>();
+
+		T::DbWeight::get().reads_writes(2 * indexed + 1, indexed + 1)
+	}
+
+	/// 链上版本为 2 时，检查不会有小猫因为超过上限而无法加入列表
+	#[cfg(feature = "try-runtime")]
+	pub fn pre_upgrade<T: Config>() -> Result<(), &'static str> {
+		use sp_std::collections::btree_map::BTreeMap;
+
+		if Pallet::<T>::on_chain_storage_version() != 2 {
+			return Ok(())
+		}
+
+		let mut owned = BTreeMap::<T::AccountId, u32>::new();
+		for owner in Owner::<T>::iter_values() {
+			*owned.entry(owner).or_default() += 1;
+		}
+		if owned.values().any(|count| *count > T::MaxKittiesOwned::get()) {
+			return Err("v3: some account owns more than MaxKittiesOwned kitties")
+		}
+
+		Ok(())
+	}
+
+	/// 检查版本已经是 3，并且每只小猫都在主人的列表中
+	#[cfg(feature = "try-runtime")]
+	pub fn post_upgrade<T: Config>() -> Result<(), &'static str> {
+		if Pallet::<T>::on_chain_storage_version() < 3 {
+			return Err("v3: storage version was not updated")
+		}
+
+		for (kitty_id, owner) in Owner::<T>::iter() {
+			if !OwnedKitties::<T>::get(&owner).contains(&kitty_id) {
+				return Err("v3: kitty is missing from its owner's list")
+			}
+		}
+
+		Ok(())
+	}
+}
@@ -0,0 +1,203 @@
+use crate as pallet_kitties;
+use crate::dna::BitSelectMixer;
+use codec::Encode;
+use frame_support::{
+	parameter_types,
+	traits::{Currency, GenesisBuild, Get, OnUnbalanced, Randomness},
+};
+use frame_system as system;
+use sp_core::H256;
+use sp_runtime::{
+	testing::Header,
+	traits::{BlakeTwo256, IdentityLookup},
+	Perbill,
+};
+use std::cell::RefCell;
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+type Block = frame_system::mocking::MockBlock<Test>;
+
+// Configure a mock runtime to test the pallet.
+frame_support::construct_runtime!(
+	pub enum Test where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic,
+	{
+		System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+		Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
+		KittiesModule: pallet_kitties::{Pallet, Call, Storage, Event<T>, Config<T>},
+	}
+);
+
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	pub const SS58Prefix: u8 = 42;
+}
+
+impl system::Config for Test {
+	type BaseCallFilter = frame_support::traits::Everything;
+	type BlockWeights = ();
+	type BlockLength = ();
+	type DbWeight = ();
+	type Origin = Origin;
+	type Call = Call;
+	type Index = u64;
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = u64;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = Event;
+	type BlockHashCount = BlockHashCount;
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = pallet_balances::AccountData<u64>;
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type SystemWeightInfo = ();
+	type SS58Prefix = SS58Prefix;
+	type OnSetCode = ();
+}
+
+parameter_types! {
+	pub const ExistentialDeposit: u64 = 1;
+	pub const MaxLocks: u32 = 50;
+}
+
+impl pallet_balances::Config for Test {
+	type MaxLocks = MaxLocks;
+	type MaxReserves = ();
+	type ReserveIdentifier = [u8; 8];
+	type Balance = u64;
+	type Event = Event;
+	type DustRemoval = ();
+	type ExistentialDeposit = ExistentialDeposit;
+	type AccountStore = System;
+	type WeightInfo = ();
+}
+
+thread_local! {
+	static RANDOM_NONCE: RefCell<u64> = RefCell::new(0);
+}
+
+// Deterministic randomness: every call hashes the subject with an increasing nonce, so kitties
+// created in the same block still get different DNA.
+pub struct TestRandomness;
+
+impl Randomness<H256, u64> for TestRandomness {
+	fn random(subject: &[u8]) -> (H256, u64) {
+		let nonce = RANDOM_NONCE.with(|n| {
+			let nonce = *n.borrow();
+			*n.borrow_mut() = nonce + 1;
+			nonce
+		});
+		let seed = (subject, nonce).using_encoded(sp_io::hashing::blake2_256);
+		(H256::from(seed), System::block_number())
+	}
+}
+
+parameter_types! {
+	pub const MaxKittiesOwned: u32 = 10;
+	pub const BreedCooldown: u64 = 10;
+	pub const MaxAuctionsEndingPerBlock: u32 = 3;
+	pub const MaxAuctionDuration: u64 = 20;
+	pub const MaxOffersExpiringPerBlock: u32 = 3;
+	pub const MaxOfferDuration: u64 = 20;
+	pub const MaxBatchSize: u32 = 5;
+	pub const MarketplaceFee: Perbill = Perbill::from_percent(10);
+	pub const CreatorRoyalty: Perbill = Perbill::from_percent(5);
+}
+
+thread_local! {
+	static KITTY_RESERVE: RefCell<u64> = RefCell::new(1_000);
+}
+
+// The deposit can be changed in tests to check that existing kitties keep the amount they
+// reserved.
+pub struct KittyReserve;
+
+impl KittyReserve {
+	pub fn get() -> u64 {
+		<Self as Get<u64>>::get()
+	}
+
+	pub fn set(reserve: u64) {
+		KITTY_RESERVE.with(|r| *r.borrow_mut() = reserve);
+	}
+}
+
+impl Get<u64> for KittyReserve {
+	fn get() -> u64 {
+		KITTY_RESERVE.with(|r| *r.borrow())
+	}
+}
+
+/// Account that collects the marketplace fees.
+pub const FEE_ACCOUNT: u64 = 100;
+
+pub struct FeeCollector;
+
+impl OnUnbalanced<pallet_balances::NegativeImbalance<Test>> for FeeCollector {
+	fn on_nonzero_unbalanced(amount: pallet_balances::NegativeImbalance<Test>) {
+		Balances::resolve_creating(&FEE_ACCOUNT, amount);
+	}
+}
+
+impl pallet_kitties::Config for Test {
+	type Event = Event;
+	type Currency = Balances;
+	type Randomness = TestRandomness;
+	type DnaMixer = BitSelectMixer;
+	type MaxKittiesOwned = MaxKittiesOwned;
+	type BreedCooldown = BreedCooldown;
+	type MaxAuctionsEndingPerBlock = MaxAuctionsEndingPerBlock;
+	type MaxAuctionDuration = MaxAuctionDuration;
+	type MaxOffersExpiringPerBlock = MaxOffersExpiringPerBlock;
+	type MaxOfferDuration = MaxOfferDuration;
+	type MarketplaceFee = MarketplaceFee;
+	type OnMarketplaceFee = FeeCollector;
+	type CreatorRoyalty = CreatorRoyalty;
+	type MaxBatchSize = MaxBatchSize;
+	type KittyReserve = KittyReserve;
+	type WeightInfo = ();
+}
+
+/// Balance given to every endowed test account.
+pub const INITIAL_BALANCE: u64 = 1_000_000;
+/// Account that can pay transaction fees but not a kitty deposit.
+pub const POOR_ACCOUNT: u64 = 9;
+
+// Build genesis storage according to the mock runtime.
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	new_test_ext_with_kitties(vec![])
+}
+
+// Build genesis storage with some kitties minted at genesis.
+pub fn new_test_ext_with_kitties(
+	kitties: Vec<(u64, [u8; 16], Option<u64>)>,
+) -> sp_io::TestExternalities {
+	RANDOM_NONCE.with(|n| *n.borrow_mut() = 0);
+	KittyReserve::set(1_000);
+
+	let mut t = system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	pallet_balances::GenesisConfig::<Test> {
+		balances: vec![
+			(1, INITIAL_BALANCE),
+			(2, INITIAL_BALANCE),
+			(3, INITIAL_BALANCE),
+			(POOR_ACCOUNT, 10),
+		],
+	}
+	.assimilate_storage(&mut t)
+	.unwrap();
+	pallet_kitties::GenesisConfig::<Test> { kitties }
+		.assimilate_storage(&mut t)
+		.unwrap();
+
+	let mut ext = sp_io::TestExternalities::new(t);
+	// Events are not deposited on the genesis block.
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
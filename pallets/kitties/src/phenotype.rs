@@ -0,0 +1,216 @@
+//! 小猫表现型: 把16字节的DNA解码成外观和稀有度，链上逻辑和前端共用同一套解释
+//!
+//! 每个基因由两个等位基因组成，各占一个字节。两个等位基因中显性的那个决定外观，
+//! 另一个是隐性基因，不表现出来但会遗传给后代。
+//!
+//! 基因布局有版本号。已经发布的版本解码结果永远不变，新的解释只能通过增加版本引入。
+//!
+//! 布局 V1:
+//!
+//! | 字节   | 基因   |
+//! |--------|--------|
+//! | 0, 1   | 毛色   |
+//! | 2, 3   | 眼型   |
+//! | 4, 5   | 花纹   |
+//! | 6, 7   | 稀有度 |
+//! | 8..16  | 保留   |
+
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+use sp_runtime::RuntimeDebug;
+
+/// 基因的一种性状，枚举中越靠前越显性
+pub trait Gene: Copy + Ord {
+	/// 一个等位基因对应的性状
+	fn from_allele(allele: u8) -> Self;
+}
+
+/// 一对等位基因表现出的性状和隐藏的性状
+fn express<G: Gene>(allele_1: u8, allele_2: u8) -> (G, G) {
+	let (gene_1, gene_2) = (G::from_allele(allele_1), G::from_allele(allele_2));
+	(gene_1.min(gene_2), gene_1.max(gene_2))
+}
+
+/// 毛色，取等位基因的高3位
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub enum BodyColor {
+	Black,
+	Ginger,
+	Grey,
+	White,
+	Cream,
+	Cinnamon,
+	Lilac,
+	Fawn,
+}
+
+impl Gene for BodyColor {
+	fn from_allele(allele: u8) -> Self {
+		use BodyColor::*;
+		[Black, Ginger, Grey, White, Cream, Cinnamon, Lilac, Fawn][(allele >> 5) as usize]
+	}
+}
+
+/// 眼型，取等位基因的高2位
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub enum EyeShape {
+	Round,
+	Almond,
+	Oval,
+	Slanted,
+}
+
+impl Gene for EyeShape {
+	fn from_allele(allele: u8) -> Self {
+		use EyeShape::*;
+		[Round, Almond, Oval, Slanted][(allele >> 6) as usize]
+	}
+}
+
+/// 花纹，取等位基因的高3位
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub enum Pattern {
+	Solid,
+	Tabby,
+	Spotted,
+	Bicolor,
+	Tortoiseshell,
+	Calico,
+	Colorpoint,
+	Marbled,
+}
+
+impl Gene for Pattern {
+	fn from_allele(allele: u8) -> Self {
+		use Pattern::*;
+		[Solid, Tabby, Spotted, Bicolor, Tortoiseshell, Calico, Colorpoint, Marbled]
+			[(allele >> 5) as usize]
+	}
+}
+
+/// 稀有度，越稀有的等级对应的等位基因取值范围越小
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub enum Rarity {
+	/// 0..=159
+	Common,
+	/// 160..=219
+	Uncommon,
+	/// 220..=245
+	Rare,
+	/// 246..=253
+	Epic,
+	/// 254..=255
+	Legendary,
+}
+
+impl Gene for Rarity {
+	fn from_allele(allele: u8) -> Self {
+		match allele {
+			0..=159 => Rarity::Common,
+			160..=219 => Rarity::Uncommon,
+			220..=245 => Rarity::Rare,
+			246..=253 => Rarity::Epic,
+			_ => Rarity::Legendary,
+		}
+	}
+}
+
+/// 基因布局的版本
+#[derive(Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub enum GeneLayout {
+	V1,
+}
+
+impl GeneLayout {
+	/// 当前使用的布局
+	pub const CURRENT: Self = GeneLayout::V1;
+
+	/// 每个基因的第一个等位基因所在的字节，第二个紧随其后。不在其中的字节是保留的
+	pub fn genes(&self) -> &'static [usize] {
+		match self {
+			GeneLayout::V1 => &[0, 2, 4, 6],
+		}
+	}
+}
+
+/// 没有表现出来、但会遗传给后代的隐性性状
+#[derive(Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct RecessiveGenes {
+	pub body_color: BodyColor,
+	pub eye_shape: EyeShape,
+	pub pattern: Pattern,
+	pub rarity: Rarity,
+}
+
+/// 小猫表现出来的外观
+#[derive(Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct Phenotype {
+	/// 解码时使用的布局
+	pub layout: GeneLayout,
+	pub body_color: BodyColor,
+	pub eye_shape: EyeShape,
+	pub pattern: Pattern,
+	pub rarity: Rarity,
+	pub hidden: RecessiveGenes,
+}
+
+impl Phenotype {
+	/// 稀有度分数: 每个性状按它在显性顺序中的位置计分，越隐性分越高；稀有度每高一级加10分。
+	/// V1 的分数在 0 到 57 之间
+	pub fn rarity_score(&self) -> u32 {
+		self.body_color as u32 +
+			self.eye_shape as u32 +
+			self.pattern as u32 +
+			10 * self.rarity as u32
+	}
+}
+
+/// 用当前布局解码DNA
+pub fn decode_phenotype(dna: &[u8; 16]) -> Phenotype {
+	decode_phenotype_with(GeneLayout::CURRENT, dna)
+}
+
+/// 用指定的布局解码DNA
+pub fn decode_phenotype_with(layout: GeneLayout, dna: &[u8; 16]) -> Phenotype {
+	match layout {
+		GeneLayout::V1 => {
+			let (body_color, hidden_body_color) = express(dna[0], dna[1]);
+			let (eye_shape, hidden_eye_shape) = express(dna[2], dna[3]);
+			let (pattern, hidden_pattern) = express(dna[4], dna[5]);
+			let (rarity, hidden_rarity) = express(dna[6], dna[7]);
+
+			Phenotype {
+				layout,
+				body_color,
+				eye_shape,
+				pattern,
+				rarity,
+				hidden: RecessiveGenes {
+					body_color: hidden_body_color,
+					eye_shape: hidden_eye_shape,
+					pattern: hidden_pattern,
+					rarity: hidden_rarity,
+				},
+			}
+		},
+	}
+}
+
+/// 用当前布局计算DNA的稀有度分数
+pub fn rarity_score(dna: &[u8; 16]) -> u32 {
+	decode_phenotype(dna).rarity_score()
+}
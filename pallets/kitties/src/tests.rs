@@ -0,0 +1,1594 @@
+use crate::{
+	dna::{BitSelectMixer, DnaMixer, MendelianMixer},
+	migrations,
+	mock::*,
+	phenotype::{
+		decode_phenotype, BodyColor, EyeShape, Gene, GeneLayout, Pattern, Phenotype, Rarity,
+		RecessiveGenes,
+	},
+	weights::WeightInfo,
+	Error, Event as KittiesEvent, Kitties, KittiesCount, KittyDeposits, NextBreedableAt,
+	OffersExpiringAt, OwnedKitties, Owner, SireOffers,
+};
+use codec::Encode;
+use frame_support::{
+	assert_noop, assert_ok, parameter_types,
+	storage::unhashed,
+	traits::{GetStorageVersion, Hooks, StorageVersion},
+	weights::Weight,
+	BoundedVec,
+};
+use sp_runtime::Perbill;
+
+fn create_kitty(who: u64) -> u32 {
+	assert_ok!(KittiesModule::create(Origin::signed(who)));
+	KittiesModule::kitties_count().unwrap() - 1
+}
+
+#[test]
+fn create_works() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(KittiesModule::create(Origin::signed(1)));
+
+		assert_eq!(KittiesModule::kitties_count(), Some(2));
+		assert_eq!(KittiesModule::owner(1), Some(1));
+		assert_eq!(KittiesModule::kitties(1).unwrap().price, None);
+		assert_eq!(KittiesModule::kitties(1).unwrap().parents, None);
+		assert_eq!(KittiesModule::kitties(1).unwrap().generation, 0);
+		assert_eq!(KittiesModule::kitties(1).unwrap().creator, Some(1));
+		assert_eq!(KittiesModule::owned_kitties(1).into_inner(), vec![1]);
+		assert_eq!(Balances::reserved_balance(1), KittyReserve::get());
+		System::assert_last_event(Event::KittiesModule(KittiesEvent::KittyCreate(1, 1)));
+	});
+}
+
+#[test]
+fn create_fails_when_kitties_count_overflow() {
+	new_test_ext().execute_with(|| {
+		KittiesCount::<Test>::put(u32::max_value());
+
+		assert_noop!(KittiesModule::create(Origin::signed(1)), Error::<Test>::KittiesCountOverflow);
+	});
+}
+
+#[test]
+fn create_fails_without_balance_for_reserve() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			KittiesModule::create(Origin::signed(POOR_ACCOUNT)),
+			Error::<Test>::NotEnoughBalanceForReserve
+		);
+	});
+}
+
+#[test]
+fn create_fails_when_exceed_max_kitties_owned() {
+	new_test_ext().execute_with(|| {
+		for _ in 0..MaxKittiesOwned::get() {
+			create_kitty(1);
+		}
+
+		assert_noop!(
+			KittiesModule::create(Origin::signed(1)),
+			Error::<Test>::ExceedMaxKittiesOwned
+		);
+	});
+}
+
+#[test]
+fn breed_works() {
+	new_test_ext().execute_with(|| {
+		let kitty_1 = create_kitty(1);
+		let kitty_2 = create_kitty(1);
+
+		System::set_block_number(5);
+		assert_ok!(KittiesModule::breed(Origin::signed(1), kitty_1, kitty_2));
+
+		assert_eq!(KittiesModule::owner(3), Some(1));
+		assert_eq!(KittiesModule::owned_kitties(1).into_inner(), vec![1, 2, 3]);
+		assert_eq!(Balances::reserved_balance(1), 3 * KittyReserve::get());
+		let child = KittiesModule::kitties(3).unwrap();
+		assert_eq!(child.parents, Some((kitty_1, kitty_2)));
+		assert_eq!(child.generation, 1);
+		assert_eq!(child.creator, Some(1));
+		assert_eq!(child.birth_block, 5);
+		System::assert_last_event(Event::KittiesModule(KittiesEvent::BreedSuccess(
+			1, kitty_1, kitty_2, 3,
+		)));
+	});
+}
+
+#[test]
+fn breed_generation_follows_older_lineage() {
+	new_test_ext().execute_with(|| {
+		let kitty_1 = create_kitty(1);
+		let kitty_2 = create_kitty(1);
+		assert_ok!(KittiesModule::breed(Origin::signed(1), kitty_1, kitty_2));
+		System::set_block_number(1 + BreedCooldown::get());
+		assert_ok!(KittiesModule::breed(Origin::signed(1), 3, kitty_1));
+
+		assert_eq!(KittiesModule::kitties(4).unwrap().generation, 2);
+	});
+}
+
+#[test]
+fn breed_fails_during_cooldown() {
+	new_test_ext().execute_with(|| {
+		let kitty_1 = create_kitty(1);
+		let kitty_2 = create_kitty(1);
+		let kitty_3 = create_kitty(1);
+		assert_ok!(KittiesModule::breed(Origin::signed(1), kitty_1, kitty_2));
+		assert_eq!(KittiesModule::next_breedable_at(kitty_1), 1 + BreedCooldown::get());
+
+		assert_noop!(
+			KittiesModule::breed(Origin::signed(1), kitty_1, kitty_3),
+			Error::<Test>::BreedCooldownNotExpired
+		);
+		assert_noop!(
+			KittiesModule::breed(Origin::signed(1), kitty_3, kitty_2),
+			Error::<Test>::BreedCooldownNotExpired
+		);
+
+		System::set_block_number(1 + BreedCooldown::get());
+		assert_ok!(KittiesModule::breed(Origin::signed(1), kitty_1, kitty_3));
+	});
+}
+
+#[test]
+fn breed_cooldown_grows_with_generation() {
+	new_test_ext().execute_with(|| {
+		let kitty_1 = create_kitty(1);
+		let kitty_2 = create_kitty(1);
+		let kitty_3 = create_kitty(1);
+		assert_ok!(KittiesModule::breed(Origin::signed(1), kitty_1, kitty_2));
+		let child = 4;
+
+		assert_ok!(KittiesModule::breed(Origin::signed(1), child, kitty_3));
+
+		// 第1代小猫休息两倍时间
+		assert_eq!(KittiesModule::next_breedable_at(kitty_3), 1 + BreedCooldown::get());
+		assert_eq!(KittiesModule::next_breedable_at(child), 1 + 2 * BreedCooldown::get());
+	});
+}
+
+#[test]
+fn breed_child_bits_come_from_parents() {
+	let kitties = vec![(1, [0xaa; 16], None), (1, [0xa0; 16], None)];
+
+	new_test_ext_with_kitties(kitties).execute_with(|| {
+		assert_ok!(KittiesModule::breed(Origin::signed(1), 1, 2));
+
+		let child = KittiesModule::kitties(3).unwrap().dna;
+		for byte in child.iter() {
+			// 父母相同的高4位必须保留，低4位只能来自 0xa 或 0x0
+			assert_eq!(byte & 0xf0, 0xa0);
+			assert_eq!(byte & 0x0f & !0x0a, 0);
+		}
+	});
+}
+
+#[test]
+fn breed_fails_with_same_parent() {
+	new_test_ext().execute_with(|| {
+		let kitty = create_kitty(1);
+
+		assert_noop!(
+			KittiesModule::breed(Origin::signed(1), kitty, kitty),
+			Error::<Test>::GenesCanNotSame
+		);
+	});
+}
+
+#[test]
+fn breed_fails_with_invalid_kitty_index() {
+	new_test_ext().execute_with(|| {
+		let kitty = create_kitty(1);
+
+		assert_noop!(
+			KittiesModule::breed(Origin::signed(1), kitty, 99),
+			Error::<Test>::InvalidKittyIndex
+		);
+	});
+}
+
+#[test]
+fn breed_fails_without_owning_both_parents() {
+	new_test_ext().execute_with(|| {
+		let kitty_1 = create_kitty(1);
+		let kitty_2 = create_kitty(2);
+
+		assert_noop!(
+			KittiesModule::breed(Origin::signed(1), kitty_1, kitty_2),
+			Error::<Test>::NotAllowedToBreed
+		);
+	});
+}
+
+#[test]
+fn breed_with_sire_offer_pays_fee() {
+	new_test_ext().execute_with(|| {
+		let kitty_1 = create_kitty(1);
+		let kitty_2 = create_kitty(2);
+		assert_ok!(KittiesModule::offer_sire(Origin::signed(2), kitty_2, None, 300));
+
+		assert_ok!(KittiesModule::breed(Origin::signed(1), kitty_1, kitty_2));
+
+		assert_eq!(KittiesModule::owner(3), Some(1));
+		assert_eq!(Balances::free_balance(1), INITIAL_BALANCE - 300 - 2 * KittyReserve::get());
+		assert_eq!(Balances::free_balance(2), INITIAL_BALANCE + 300 - KittyReserve::get());
+		let fee_paid = Event::KittiesModule(KittiesEvent::SireFeePaid(1, 2, kitty_2, 300));
+		assert!(System::events().iter().any(|record| record.event == fee_paid));
+		// 授权可以重复使用
+		assert!(SireOffers::<Test>::contains_key(kitty_2));
+	});
+}
+
+#[test]
+fn breed_with_named_sire_offer_rejects_other_breeders() {
+	new_test_ext().execute_with(|| {
+		let kitty_1 = create_kitty(1);
+		let kitty_2 = create_kitty(2);
+		assert_ok!(KittiesModule::offer_sire(Origin::signed(2), kitty_2, Some(3), 300));
+
+		assert_noop!(
+			KittiesModule::breed(Origin::signed(1), kitty_1, kitty_2),
+			Error::<Test>::NotAllowedToBreed
+		);
+	});
+}
+
+#[test]
+fn offer_sire_fails_when_not_owner() {
+	new_test_ext().execute_with(|| {
+		let kitty = create_kitty(1);
+
+		assert_noop!(
+			KittiesModule::offer_sire(Origin::signed(2), kitty, None, 300),
+			Error::<Test>::NotOwner
+		);
+	});
+}
+
+#[test]
+fn offer_sire_fails_to_yourself() {
+	new_test_ext().execute_with(|| {
+		let kitty = create_kitty(1);
+
+		assert_noop!(
+			KittiesModule::offer_sire(Origin::signed(1), kitty, Some(1), 300),
+			Error::<Test>::CanNotYourSelf
+		);
+	});
+}
+
+#[test]
+fn cancel_sire_offer_works() {
+	new_test_ext().execute_with(|| {
+		let kitty = create_kitty(1);
+		assert_ok!(KittiesModule::offer_sire(Origin::signed(1), kitty, None, 300));
+
+		assert_ok!(KittiesModule::cancel_sire_offer(Origin::signed(1), kitty));
+
+		assert!(!SireOffers::<Test>::contains_key(kitty));
+		System::assert_last_event(Event::KittiesModule(KittiesEvent::SireOfferCancelled(1, kitty)));
+		assert_noop!(
+			KittiesModule::cancel_sire_offer(Origin::signed(1), kitty),
+			Error::<Test>::SireOfferNotExist
+		);
+	});
+}
+
+#[test]
+fn set_price_works() {
+	new_test_ext().execute_with(|| {
+		let kitty = create_kitty(1);
+
+		assert_ok!(KittiesModule::set_price(Origin::signed(1), kitty, 500));
+
+		assert_eq!(KittiesModule::kitties(kitty).unwrap().price, Some(500));
+		System::assert_last_event(Event::KittiesModule(KittiesEvent::SetPriceSuccess(
+			1, kitty, 500,
+		)));
+	});
+}
+
+#[test]
+fn set_price_fails_with_invalid_kitty_index() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			KittiesModule::set_price(Origin::signed(1), 99, 500),
+			Error::<Test>::InvalidKittyIndex
+		);
+	});
+}
+
+#[test]
+fn set_price_fails_when_not_owner() {
+	new_test_ext().execute_with(|| {
+		let kitty = create_kitty(1);
+
+		assert_noop!(
+			KittiesModule::set_price(Origin::signed(2), kitty, 500),
+			Error::<Test>::NotOwner
+		);
+	});
+}
+
+#[test]
+fn set_price_fails_with_zero_price() {
+	new_test_ext().execute_with(|| {
+		let kitty = create_kitty(1);
+
+		assert_noop!(
+			KittiesModule::set_price(Origin::signed(1), kitty, 0),
+			Error::<Test>::PriceNotZero
+		);
+	});
+}
+
+#[test]
+fn unlist_works() {
+	new_test_ext().execute_with(|| {
+		let kitty = create_kitty(1);
+		assert_ok!(KittiesModule::set_price(Origin::signed(1), kitty, 500));
+
+		assert_ok!(KittiesModule::unlist(Origin::signed(1), kitty));
+
+		assert_eq!(KittiesModule::kitties(kitty).unwrap().price, None);
+		System::assert_last_event(Event::KittiesModule(KittiesEvent::KittyUnlisted(1, kitty)));
+		assert_noop!(
+			KittiesModule::buy_kitty(Origin::signed(2), kitty, 500),
+			Error::<Test>::PriceIsNone
+		);
+	});
+}
+
+#[test]
+fn unlist_fails_with_invalid_arguments() {
+	new_test_ext().execute_with(|| {
+		let kitty = create_kitty(1);
+
+		assert_noop!(
+			KittiesModule::unlist(Origin::signed(1), 99),
+			Error::<Test>::InvalidKittyIndex
+		);
+		assert_noop!(KittiesModule::unlist(Origin::signed(1), kitty), Error::<Test>::PriceIsNone);
+
+		assert_ok!(KittiesModule::set_price(Origin::signed(1), kitty, 500));
+		assert_noop!(KittiesModule::unlist(Origin::signed(2), kitty), Error::<Test>::NotOwner);
+	});
+}
+
+#[test]
+fn buy_kitty_works() {
+	new_test_ext().execute_with(|| {
+		let kitty = create_kitty(1);
+		assert_ok!(KittiesModule::set_price(Origin::signed(1), kitty, 500));
+
+		assert_ok!(KittiesModule::buy_kitty(Origin::signed(2), kitty, 500));
+
+		assert_eq!(KittiesModule::owner(kitty), Some(2));
+		assert_eq!(KittiesModule::kitties(kitty).unwrap().price, None);
+		assert!(KittiesModule::owned_kitties(1).is_empty());
+		assert_eq!(KittiesModule::owned_kitties(2).into_inner(), vec![kitty]);
+		// 押金随小猫转给买家
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert_eq!(Balances::reserved_balance(2), KittyReserve::get());
+		// 卖家就是创作者，不收版税，只扣除 10% 的市场手续费
+		assert_eq!(Balances::free_balance(1), INITIAL_BALANCE + 450);
+		assert_eq!(Balances::free_balance(2), INITIAL_BALANCE - 500 - KittyReserve::get());
+		assert_eq!(Balances::free_balance(FEE_ACCOUNT), 50);
+		System::assert_last_event(Event::KittiesModule(KittiesEvent::KittySold(
+			2, 1, kitty, 500, 50, 0,
+		)));
+	});
+}
+
+#[test]
+fn buy_kitty_pays_royalty_to_creator_on_resale() {
+	new_test_ext().execute_with(|| {
+		let kitty = create_kitty(1);
+		assert_ok!(KittiesModule::transfer(Origin::signed(1), 2, kitty));
+		assert_ok!(KittiesModule::set_price(Origin::signed(2), kitty, 1_000));
+
+		assert_ok!(KittiesModule::buy_kitty(Origin::signed(3), kitty, 1_000));
+
+		assert_eq!(KittiesModule::kitties(kitty).unwrap().creator, Some(1));
+		assert_eq!(Balances::free_balance(1), INITIAL_BALANCE + 50);
+		assert_eq!(Balances::free_balance(2), INITIAL_BALANCE + 850);
+		assert_eq!(Balances::free_balance(3), INITIAL_BALANCE - 1_000 - KittyReserve::get());
+		assert_eq!(Balances::free_balance(FEE_ACCOUNT), 100);
+		System::assert_last_event(Event::KittiesModule(KittiesEvent::KittySold(
+			3, 2, kitty, 1_000, 100, 50,
+		)));
+	});
+}
+
+#[test]
+fn buy_kitty_pays_no_royalty_for_kitty_without_creator() {
+	new_test_ext().execute_with(|| {
+		let kitty = create_kitty(1);
+		// 升级前铸造的小猫没有创作者记录
+		Kitties::<Test>::mutate(kitty, |kitty| kitty.as_mut().unwrap().creator = None);
+		assert_ok!(KittiesModule::set_price(Origin::signed(1), kitty, 1_000));
+
+		assert_ok!(KittiesModule::buy_kitty(Origin::signed(2), kitty, 1_000));
+
+		assert_eq!(Balances::free_balance(1), INITIAL_BALANCE + 900);
+		System::assert_last_event(Event::KittiesModule(KittiesEvent::KittySold(
+			2, 1, kitty, 1_000, 100, 0,
+		)));
+	});
+}
+
+#[test]
+fn buy_kitty_fails_with_invalid_kitty_index() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			KittiesModule::buy_kitty(Origin::signed(2), 99, 500),
+			Error::<Test>::InvalidKittyIndex
+		);
+	});
+}
+
+#[test]
+fn buy_kitty_fails_when_price_is_none() {
+	new_test_ext().execute_with(|| {
+		let kitty = create_kitty(1);
+
+		assert_noop!(
+			KittiesModule::buy_kitty(Origin::signed(2), kitty, 500),
+			Error::<Test>::PriceIsNone
+		);
+	});
+}
+
+#[test]
+fn buy_kitty_fails_when_money_not_enough() {
+	new_test_ext().execute_with(|| {
+		let kitty = create_kitty(1);
+		assert_ok!(KittiesModule::set_price(Origin::signed(1), kitty, INITIAL_BALANCE + 1));
+
+		assert_noop!(
+			KittiesModule::buy_kitty(Origin::signed(2), kitty, INITIAL_BALANCE + 1),
+			Error::<Test>::MoneyNotEnough
+		);
+	});
+}
+
+#[test]
+fn buy_kitty_fails_when_price_above_max_price() {
+	new_test_ext().execute_with(|| {
+		let kitty = create_kitty(1);
+		assert_ok!(KittiesModule::set_price(Origin::signed(1), kitty, 500));
+		// 卖家抢在买家之前抬价
+		assert_ok!(KittiesModule::set_price(Origin::signed(1), kitty, 800));
+
+		assert_noop!(
+			KittiesModule::buy_kitty(Origin::signed(2), kitty, 500),
+			Error::<Test>::PriceTooHigh
+		);
+
+		// 买家愿意支付更高的价格时，按实际售价成交
+		assert_ok!(KittiesModule::buy_kitty(Origin::signed(2), kitty, 1_000));
+		assert_eq!(Balances::free_balance(2), INITIAL_BALANCE - 800 - KittyReserve::get());
+	});
+}
+
+#[test]
+fn buy_kitty_fails_when_buying_own_kitty() {
+	new_test_ext().execute_with(|| {
+		let kitty = create_kitty(1);
+		assert_ok!(KittiesModule::set_price(Origin::signed(1), kitty, 500));
+
+		assert_noop!(
+			KittiesModule::buy_kitty(Origin::signed(1), kitty, 500),
+			Error::<Test>::CanNotYourSelf
+		);
+		assert_eq!(KittiesModule::owned_kitties(1).into_inner(), vec![kitty]);
+		assert_eq!(KittiesModule::owner(kitty), Some(1));
+	});
+}
+
+#[test]
+fn transfer_works() {
+	new_test_ext().execute_with(|| {
+		let kitty = create_kitty(1);
+		assert_ok!(KittiesModule::set_price(Origin::signed(1), kitty, 500));
+
+		assert_ok!(KittiesModule::transfer(Origin::signed(1), 2, kitty));
+
+		assert_eq!(Owner::<Test>::get(kitty), Some(2));
+		assert_eq!(KittiesModule::kitties(kitty).unwrap().price, None);
+		assert_eq!(KittiesModule::owned_kitties(2).into_inner(), vec![kitty]);
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert_eq!(Balances::reserved_balance(2), KittyReserve::get());
+		System::assert_last_event(Event::KittiesModule(KittiesEvent::Transfer(1, kitty, 2)));
+	});
+}
+
+#[test]
+fn transfer_clears_sire_offer() {
+	new_test_ext().execute_with(|| {
+		let kitty = create_kitty(1);
+		assert_ok!(KittiesModule::offer_sire(Origin::signed(1), kitty, None, 300));
+
+		assert_ok!(KittiesModule::transfer(Origin::signed(1), 2, kitty));
+
+		assert!(!SireOffers::<Test>::contains_key(kitty));
+	});
+}
+
+#[test]
+fn transfer_fails_when_not_owner() {
+	new_test_ext().execute_with(|| {
+		let kitty = create_kitty(1);
+
+		assert_noop!(KittiesModule::transfer(Origin::signed(2), 3, kitty), Error::<Test>::NotOwner);
+	});
+}
+
+#[test]
+fn transfer_fails_to_yourself() {
+	new_test_ext().execute_with(|| {
+		let kitty = create_kitty(1);
+
+		assert_noop!(
+			KittiesModule::transfer(Origin::signed(1), 1, kitty),
+			Error::<Test>::CanNotYourSelf
+		);
+	});
+}
+
+#[test]
+fn transfer_fails_when_recipient_cannot_reserve() {
+	new_test_ext().execute_with(|| {
+		let kitty = create_kitty(1);
+
+		assert_noop!(
+			KittiesModule::transfer(Origin::signed(1), POOR_ACCOUNT, kitty),
+			Error::<Test>::NotEnoughBalanceForReserve
+		);
+		assert_eq!(Balances::reserved_balance(POOR_ACCOUNT), 0);
+	});
+}
+
+#[test]
+fn create_batch_works() {
+	new_test_ext().execute_with(|| {
+		create_kitty(1);
+
+		assert_ok!(KittiesModule::create_batch(Origin::signed(2), 3));
+
+		assert_eq!(KittiesModule::kitties_count(), Some(5));
+		assert_eq!(KittiesModule::owned_kitties(2).into_inner(), vec![2, 3, 4]);
+		assert_eq!(Balances::reserved_balance(2), 3 * KittyReserve::get());
+		// 同一批小猫的DNA各不相同
+		let dnas: Vec<_> = (2..=4).map(|id| KittiesModule::kitties(id).unwrap().dna).collect();
+		assert!(dnas[0] != dnas[1] && dnas[1] != dnas[2] && dnas[0] != dnas[2]);
+
+		System::assert_has_event(Event::KittiesModule(KittiesEvent::KittyCreate(2, 3)));
+		System::assert_last_event(Event::KittiesModule(KittiesEvent::BatchCreated(2, 2, 3)));
+	});
+}
+
+#[test]
+fn create_batch_fails_with_invalid_count() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(KittiesModule::create_batch(Origin::signed(1), 0), Error::<Test>::EmptyBatch);
+		assert_noop!(
+			KittiesModule::create_batch(Origin::signed(1), MaxBatchSize::get() + 1),
+			Error::<Test>::BatchTooLarge
+		);
+	});
+}
+
+#[test]
+fn create_batch_is_atomic() {
+	new_test_ext().execute_with(|| {
+		for _ in 0..MaxKittiesOwned::get() - 2 {
+			create_kitty(1);
+		}
+
+		assert_noop!(
+			KittiesModule::create_batch(Origin::signed(1), 3),
+			Error::<Test>::ExceedMaxKittiesOwned
+		);
+		assert_eq!(KittiesModule::owned_kitties(1).len() as u32, MaxKittiesOwned::get() - 2);
+	});
+}
+
+#[test]
+fn transfer_batch_works() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(KittiesModule::create_batch(Origin::signed(1), 3));
+		assert_ok!(KittiesModule::set_price(Origin::signed(1), 2, 500));
+
+		assert_ok!(KittiesModule::transfer_batch(Origin::signed(1), vec![(2, 1), (3, 2), (2, 3)]));
+
+		assert!(KittiesModule::owned_kitties(1).is_empty());
+		assert_eq!(KittiesModule::owned_kitties(2).into_inner(), vec![1, 3]);
+		assert_eq!(KittiesModule::owned_kitties(3).into_inner(), vec![2]);
+		assert_eq!(KittiesModule::kitties(2).unwrap().price, None);
+		assert_eq!(Balances::reserved_balance(1), 0);
+		System::assert_has_event(Event::KittiesModule(KittiesEvent::Transfer(1, 2, 3)));
+		System::assert_last_event(Event::KittiesModule(KittiesEvent::BatchTransferred(1, 3)));
+	});
+}
+
+#[test]
+fn transfer_batch_is_atomic() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(KittiesModule::create_batch(Origin::signed(1), 2));
+		let other = create_kitty(2);
+
+		assert_noop!(
+			KittiesModule::transfer_batch(Origin::signed(1), vec![(2, 1), (3, other)]),
+			Error::<Test>::NotOwner
+		);
+		assert_noop!(
+			KittiesModule::transfer_batch(Origin::signed(1), vec![(2, 1), (2, 1)]),
+			Error::<Test>::NotOwner
+		);
+		assert_eq!(KittiesModule::owner(1), Some(1));
+	});
+}
+
+#[test]
+fn transfer_batch_fails_with_invalid_size() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			KittiesModule::transfer_batch(Origin::signed(1), vec![]),
+			Error::<Test>::EmptyBatch
+		);
+		let transfers = (1..=MaxBatchSize::get() + 1).map(|id| (2, id)).collect();
+		assert_noop!(
+			KittiesModule::transfer_batch(Origin::signed(1), transfers),
+			Error::<Test>::BatchTooLarge
+		);
+	});
+}
+
+#[test]
+fn burn_works() {
+	new_test_ext().execute_with(|| {
+		let kitty_1 = create_kitty(1);
+		let kitty_2 = create_kitty(1);
+		assert_ok!(KittiesModule::breed(Origin::signed(1), kitty_1, kitty_2));
+		assert_ok!(KittiesModule::set_price(Origin::signed(1), kitty_1, 500));
+		assert_ok!(KittiesModule::offer_sire(Origin::signed(1), kitty_1, None, 300));
+
+		assert_ok!(KittiesModule::burn(Origin::signed(1), kitty_1));
+
+		assert!(KittiesModule::kitties(kitty_1).is_none());
+		assert!(KittiesModule::owner(kitty_1).is_none());
+		assert!(KittiesModule::sire_offers(kitty_1).is_none());
+		assert!(!NextBreedableAt::<Test>::contains_key(kitty_1));
+		assert!(!KittyDeposits::<Test>::contains_key(kitty_1));
+		assert_eq!(KittiesModule::owned_kitties(1).into_inner(), vec![kitty_2, 3]);
+		assert_eq!(Balances::reserved_balance(1), 2 * KittyReserve::get());
+		System::assert_last_event(Event::KittiesModule(KittiesEvent::KittyBurned(1, kitty_1)));
+
+		// 索引不会被重新使用
+		assert_eq!(create_kitty(1), 4);
+	});
+}
+
+#[test]
+fn recorded_deposit_is_returned_after_reserve_changes() {
+	new_test_ext().execute_with(|| {
+		let kitty = create_kitty(1);
+		assert_eq!(KittiesModule::kitty_deposit(kitty), 1_000);
+
+		// 押金调整后，原主人取回当初质押的金额，新主人按新的金额质押
+		KittyReserve::set(2_000);
+		assert_ok!(KittiesModule::transfer(Origin::signed(1), 2, kitty));
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert_eq!(Balances::reserved_balance(2), 2_000);
+		assert_eq!(KittiesModule::kitty_deposit(kitty), 2_000);
+
+		KittyReserve::set(500);
+		assert_ok!(KittiesModule::burn(Origin::signed(2), kitty));
+		assert_eq!(Balances::reserved_balance(2), 0);
+		assert_eq!(Balances::free_balance(2), INITIAL_BALANCE);
+	});
+}
+
+#[test]
+fn burn_removes_empty_owner_index() {
+	new_test_ext().execute_with(|| {
+		let kitty = create_kitty(1);
+
+		assert_ok!(KittiesModule::burn(Origin::signed(1), kitty));
+
+		assert!(!OwnedKitties::<Test>::contains_key(1));
+		assert_eq!(Balances::free_balance(1), INITIAL_BALANCE);
+		assert_noop!(
+			KittiesModule::transfer(Origin::signed(1), 2, kitty),
+			Error::<Test>::InvalidKittyIndex
+		);
+	});
+}
+
+#[test]
+fn burn_fails_with_invalid_arguments() {
+	new_test_ext().execute_with(|| {
+		let kitty = create_kitty(1);
+
+		assert_noop!(KittiesModule::burn(Origin::signed(1), 99), Error::<Test>::InvalidKittyIndex);
+		assert_noop!(KittiesModule::burn(Origin::signed(2), kitty), Error::<Test>::NotOwner);
+
+		assert_ok!(KittiesModule::create_auction(Origin::signed(1), kitty, 500, 10));
+		assert_noop!(KittiesModule::burn(Origin::signed(1), kitty), Error::<Test>::KittyInAuction);
+	});
+}
+
+// 推进到第 n 个区块，并执行 on_initialize
+fn run_to_block(n: u64) {
+	while System::block_number() < n {
+		System::set_block_number(System::block_number() + 1);
+		KittiesModule::on_initialize(System::block_number());
+	}
+}
+
+#[test]
+fn create_auction_works() {
+	new_test_ext().execute_with(|| {
+		let kitty = create_kitty(1);
+		assert_ok!(KittiesModule::set_price(Origin::signed(1), kitty, 800));
+
+		assert_ok!(KittiesModule::create_auction(Origin::signed(1), kitty, 500, 10));
+
+		let auction = KittiesModule::auctions(kitty).unwrap();
+		assert_eq!(auction.seller, 1);
+		assert_eq!(auction.reserve, 500);
+		assert_eq!(auction.end_block, 10);
+		assert_eq!(auction.top_bid, None);
+		assert_eq!(KittiesModule::auctions_ending_at(10).into_inner(), vec![kitty]);
+		// 拍卖期间取消固定价格
+		assert_eq!(KittiesModule::kitties(kitty).unwrap().price, None);
+		System::assert_last_event(Event::KittiesModule(KittiesEvent::AuctionCreated(
+			1, kitty, 500, 10,
+		)));
+	});
+}
+
+#[test]
+fn create_auction_fails_with_invalid_arguments() {
+	new_test_ext().execute_with(|| {
+		let kitty = create_kitty(1);
+
+		assert_noop!(
+			KittiesModule::create_auction(Origin::signed(1), 99, 500, 10),
+			Error::<Test>::InvalidKittyIndex
+		);
+		assert_noop!(
+			KittiesModule::create_auction(Origin::signed(2), kitty, 500, 10),
+			Error::<Test>::NotOwner
+		);
+		assert_noop!(
+			KittiesModule::create_auction(Origin::signed(1), kitty, 500, 1),
+			Error::<Test>::InvalidAuctionEnd
+		);
+		assert_noop!(
+			KittiesModule::create_auction(Origin::signed(1), kitty, 500, 22),
+			Error::<Test>::AuctionDurationTooLong
+		);
+
+		assert_ok!(KittiesModule::create_auction(Origin::signed(1), kitty, 500, 10));
+		assert_noop!(
+			KittiesModule::create_auction(Origin::signed(1), kitty, 500, 20),
+			Error::<Test>::KittyInAuction
+		);
+	});
+}
+
+#[test]
+fn create_auction_fails_when_too_many_end_in_one_block() {
+	new_test_ext().execute_with(|| {
+		for _ in 0..MaxAuctionsEndingPerBlock::get() {
+			let kitty = create_kitty(1);
+			assert_ok!(KittiesModule::create_auction(Origin::signed(1), kitty, 500, 10));
+		}
+		let kitty = create_kitty(1);
+
+		assert_noop!(
+			KittiesModule::create_auction(Origin::signed(1), kitty, 500, 10),
+			Error::<Test>::TooManyAuctionsEnding
+		);
+		assert_ok!(KittiesModule::create_auction(Origin::signed(1), kitty, 500, 11));
+	});
+}
+
+#[test]
+fn auction_locks_kitty() {
+	new_test_ext().execute_with(|| {
+		let kitty = create_kitty(1);
+		let other = create_kitty(1);
+		assert_ok!(KittiesModule::create_auction(Origin::signed(1), kitty, 500, 10));
+
+		assert_noop!(
+			KittiesModule::set_price(Origin::signed(1), kitty, 800),
+			Error::<Test>::KittyInAuction
+		);
+		assert_noop!(
+			KittiesModule::transfer(Origin::signed(1), 2, kitty),
+			Error::<Test>::KittyInAuction
+		);
+		assert_noop!(
+			KittiesModule::breed(Origin::signed(1), kitty, other),
+			Error::<Test>::KittyInAuction
+		);
+		assert_noop!(
+			KittiesModule::buy_kitty(Origin::signed(2), kitty, 500),
+			Error::<Test>::PriceIsNone
+		);
+	});
+}
+
+#[test]
+fn bid_reserves_funds_and_refunds_outbid_bidder() {
+	new_test_ext().execute_with(|| {
+		let kitty = create_kitty(1);
+		assert_ok!(KittiesModule::create_auction(Origin::signed(1), kitty, 500, 10));
+
+		assert_ok!(KittiesModule::bid(Origin::signed(2), kitty, 500));
+		assert_eq!(Balances::reserved_balance(2), 500);
+		System::assert_last_event(Event::KittiesModule(KittiesEvent::BidPlaced(2, kitty, 500)));
+
+		assert_ok!(KittiesModule::bid(Origin::signed(3), kitty, 600));
+		assert_eq!(Balances::reserved_balance(2), 0);
+		assert_eq!(Balances::reserved_balance(3), 600);
+
+		// 最高出价人可以继续加价
+		assert_ok!(KittiesModule::bid(Origin::signed(3), kitty, 700));
+		assert_eq!(Balances::reserved_balance(3), 700);
+		assert_eq!(KittiesModule::auctions(kitty).unwrap().top_bid, Some((3, 700)));
+	});
+}
+
+#[test]
+fn bid_fails_with_invalid_arguments() {
+	new_test_ext().execute_with(|| {
+		let kitty = create_kitty(1);
+
+		assert_noop!(
+			KittiesModule::bid(Origin::signed(2), kitty, 500),
+			Error::<Test>::AuctionNotExist
+		);
+
+		assert_ok!(KittiesModule::create_auction(Origin::signed(1), kitty, 500, 10));
+		assert_noop!(
+			KittiesModule::bid(Origin::signed(1), kitty, 500),
+			Error::<Test>::CanNotYourSelf
+		);
+		assert_noop!(KittiesModule::bid(Origin::signed(2), kitty, 499), Error::<Test>::BidTooLow);
+		assert_noop!(
+			KittiesModule::bid(Origin::signed(2), kitty, INITIAL_BALANCE + 1),
+			Error::<Test>::MoneyNotEnough
+		);
+
+		assert_ok!(KittiesModule::bid(Origin::signed(2), kitty, 600));
+		assert_noop!(KittiesModule::bid(Origin::signed(3), kitty, 600), Error::<Test>::BidTooLow);
+
+		System::set_block_number(10);
+		assert_noop!(
+			KittiesModule::bid(Origin::signed(3), kitty, 700),
+			Error::<Test>::AuctionEnded
+		);
+	});
+}
+
+#[test]
+fn auction_settles_to_highest_bidder() {
+	new_test_ext().execute_with(|| {
+		let kitty = create_kitty(1);
+		assert_ok!(KittiesModule::create_auction(Origin::signed(1), kitty, 500, 10));
+		assert_ok!(KittiesModule::bid(Origin::signed(2), kitty, 500));
+		assert_ok!(KittiesModule::bid(Origin::signed(3), kitty, 600));
+
+		run_to_block(9);
+		assert!(KittiesModule::auctions(kitty).is_some());
+
+		run_to_block(10);
+		assert!(KittiesModule::auctions(kitty).is_none());
+		assert!(KittiesModule::auctions_ending_at(10).is_empty());
+		assert_eq!(KittiesModule::owner(kitty), Some(3));
+		assert_eq!(KittiesModule::owned_kitties(3).into_inner(), vec![kitty]);
+		assert_eq!(Balances::free_balance(1), INITIAL_BALANCE + 540);
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert_eq!(Balances::free_balance(3), INITIAL_BALANCE - 600 - KittyReserve::get());
+		assert_eq!(Balances::reserved_balance(3), KittyReserve::get());
+		assert_eq!(Balances::reserved_balance(2), 0);
+		System::assert_last_event(Event::KittiesModule(KittiesEvent::AuctionWon(
+			1, 3, kitty, 600, 60, 0,
+		)));
+
+		// 结算后小猫解锁
+		assert_ok!(KittiesModule::transfer(Origin::signed(3), 2, kitty));
+	});
+}
+
+#[test]
+fn auction_without_bids_is_unsold() {
+	new_test_ext().execute_with(|| {
+		let kitty = create_kitty(1);
+		assert_ok!(KittiesModule::create_auction(Origin::signed(1), kitty, 500, 10));
+
+		run_to_block(10);
+
+		assert!(KittiesModule::auctions(kitty).is_none());
+		assert_eq!(KittiesModule::owner(kitty), Some(1));
+		System::assert_last_event(Event::KittiesModule(KittiesEvent::AuctionUnsold(1, kitty)));
+		assert_ok!(KittiesModule::set_price(Origin::signed(1), kitty, 800));
+	});
+}
+
+#[test]
+fn auction_is_unsold_when_winner_cannot_take_kitty() {
+	new_test_ext().execute_with(|| {
+		let kitty = create_kitty(1);
+		assert_ok!(KittiesModule::create_auction(Origin::signed(1), kitty, 500, 10));
+		assert_ok!(KittiesModule::bid(Origin::signed(2), kitty, 500));
+		// 出价之后买家的小猫达到上限
+		for _ in 0..MaxKittiesOwned::get() {
+			create_kitty(2);
+		}
+
+		run_to_block(10);
+
+		assert_eq!(KittiesModule::owner(kitty), Some(1));
+		assert_eq!(Balances::free_balance(1), INITIAL_BALANCE - KittyReserve::get());
+		assert_eq!(
+			Balances::reserved_balance(2),
+			KittyReserve::get() * MaxKittiesOwned::get() as u64
+		);
+		System::assert_last_event(Event::KittiesModule(KittiesEvent::AuctionUnsold(1, kitty)));
+	});
+}
+
+#[test]
+fn make_offer_works() {
+	new_test_ext().execute_with(|| {
+		let kitty = create_kitty(1);
+
+		assert_ok!(KittiesModule::make_offer(Origin::signed(2), kitty, 700, 10));
+
+		let offer = KittiesModule::offers(kitty, 2).unwrap();
+		assert_eq!(offer.amount, 700);
+		assert_eq!(offer.expires_at, 10);
+		assert_eq!(KittiesModule::offers_expiring_at(10).into_inner(), vec![(kitty, 2)]);
+		assert_eq!(KittiesModule::next_offer_cleanup(), Some(1));
+		assert_eq!(Balances::reserved_balance(2), 700);
+		System::assert_last_event(Event::KittiesModule(KittiesEvent::OfferMade(2, kitty, 700, 10)));
+	});
+}
+
+#[test]
+fn make_offer_fails_with_invalid_arguments() {
+	new_test_ext().execute_with(|| {
+		let kitty = create_kitty(1);
+
+		assert_noop!(
+			KittiesModule::make_offer(Origin::signed(2), 99, 700, 10),
+			Error::<Test>::InvalidKittyIndex
+		);
+		assert_noop!(
+			KittiesModule::make_offer(Origin::signed(1), kitty, 700, 10),
+			Error::<Test>::CanNotYourSelf
+		);
+		assert_noop!(
+			KittiesModule::make_offer(Origin::signed(2), kitty, 0, 10),
+			Error::<Test>::PriceNotZero
+		);
+		assert_noop!(
+			KittiesModule::make_offer(Origin::signed(2), kitty, 700, 1),
+			Error::<Test>::InvalidOfferExpiry
+		);
+		assert_noop!(
+			KittiesModule::make_offer(Origin::signed(2), kitty, 700, 22),
+			Error::<Test>::OfferDurationTooLong
+		);
+		assert_noop!(
+			KittiesModule::make_offer(Origin::signed(2), kitty, INITIAL_BALANCE + 1, 10),
+			Error::<Test>::MoneyNotEnough
+		);
+
+		assert_ok!(KittiesModule::make_offer(Origin::signed(2), kitty, 700, 10));
+		assert_noop!(
+			KittiesModule::make_offer(Origin::signed(2), kitty, 800, 10),
+			Error::<Test>::OfferAlreadyExists
+		);
+	});
+}
+
+#[test]
+fn make_offer_fails_when_too_many_expire_in_one_block() {
+	new_test_ext().execute_with(|| {
+		for _ in 0..MaxOffersExpiringPerBlock::get() {
+			let kitty = create_kitty(1);
+			assert_ok!(KittiesModule::make_offer(Origin::signed(2), kitty, 700, 10));
+		}
+		let kitty = create_kitty(1);
+
+		assert_noop!(
+			KittiesModule::make_offer(Origin::signed(2), kitty, 700, 10),
+			Error::<Test>::TooManyOffersExpiring
+		);
+		assert_ok!(KittiesModule::make_offer(Origin::signed(2), kitty, 700, 11));
+	});
+}
+
+#[test]
+fn accept_offer_works() {
+	new_test_ext().execute_with(|| {
+		let kitty = create_kitty(1);
+		assert_ok!(KittiesModule::set_price(Origin::signed(1), kitty, 5_000));
+		assert_ok!(KittiesModule::make_offer(Origin::signed(2), kitty, 1_000, 10));
+		assert_ok!(KittiesModule::make_offer(Origin::signed(3), kitty, 800, 10));
+
+		assert_ok!(KittiesModule::accept_offer(Origin::signed(1), kitty, 2));
+
+		assert_eq!(KittiesModule::owner(kitty), Some(2));
+		assert_eq!(KittiesModule::kitties(kitty).unwrap().price, None);
+		assert!(KittiesModule::offers(kitty, 2).is_none());
+		assert_eq!(KittiesModule::offers_expiring_at(10).into_inner(), vec![(kitty, 3)]);
+		// 卖家就是创作者，只扣除 10% 的市场手续费
+		assert_eq!(Balances::free_balance(1), INITIAL_BALANCE + 900);
+		assert_eq!(Balances::free_balance(2), INITIAL_BALANCE - 1_000 - KittyReserve::get());
+		assert_eq!(Balances::reserved_balance(2), KittyReserve::get());
+		assert_eq!(Balances::free_balance(FEE_ACCOUNT), 100);
+		System::assert_last_event(Event::KittiesModule(KittiesEvent::OfferAccepted(
+			1, 2, kitty, 1_000, 100, 0,
+		)));
+
+		// 其他报价仍然有效，新主人可以接受
+		assert_ok!(KittiesModule::accept_offer(Origin::signed(2), kitty, 3));
+		assert_eq!(KittiesModule::owner(kitty), Some(3));
+	});
+}
+
+#[test]
+fn accept_offer_fails_with_invalid_arguments() {
+	new_test_ext().execute_with(|| {
+		let kitty = create_kitty(1);
+
+		assert_noop!(
+			KittiesModule::accept_offer(Origin::signed(1), 99, 2),
+			Error::<Test>::InvalidKittyIndex
+		);
+		assert_noop!(
+			KittiesModule::accept_offer(Origin::signed(1), kitty, 2),
+			Error::<Test>::OfferNotExist
+		);
+
+		assert_ok!(KittiesModule::make_offer(Origin::signed(2), kitty, 700, 10));
+		assert_noop!(
+			KittiesModule::accept_offer(Origin::signed(3), kitty, 2),
+			Error::<Test>::NotOwner
+		);
+
+		System::set_block_number(10);
+		assert_noop!(
+			KittiesModule::accept_offer(Origin::signed(1), kitty, 2),
+			Error::<Test>::OfferExpired
+		);
+	});
+}
+
+#[test]
+fn accept_offer_fails_for_own_offer() {
+	new_test_ext().execute_with(|| {
+		let kitty = create_kitty(1);
+		assert_ok!(KittiesModule::make_offer(Origin::signed(2), kitty, 700, 10));
+		assert_ok!(KittiesModule::set_price(Origin::signed(1), kitty, 500));
+		assert_ok!(KittiesModule::buy_kitty(Origin::signed(2), kitty, 500));
+
+		assert_noop!(
+			KittiesModule::accept_offer(Origin::signed(2), kitty, 2),
+			Error::<Test>::CanNotYourSelf
+		);
+		assert_eq!(KittiesModule::owned_kitties(2).into_inner(), vec![kitty]);
+
+		// 之前的报价仍然可以撤回
+		assert_ok!(KittiesModule::withdraw_offer(Origin::signed(2), kitty));
+		assert_eq!(Balances::reserved_balance(2), KittyReserve::get());
+	});
+}
+
+#[test]
+fn accept_offer_fails_when_kitty_in_auction() {
+	new_test_ext().execute_with(|| {
+		let kitty = create_kitty(1);
+		assert_ok!(KittiesModule::make_offer(Origin::signed(2), kitty, 700, 10));
+		assert_ok!(KittiesModule::create_auction(Origin::signed(1), kitty, 500, 10));
+
+		assert_noop!(
+			KittiesModule::accept_offer(Origin::signed(1), kitty, 2),
+			Error::<Test>::KittyInAuction
+		);
+	});
+}
+
+#[test]
+fn withdraw_offer_works() {
+	new_test_ext().execute_with(|| {
+		let kitty = create_kitty(1);
+		assert_noop!(
+			KittiesModule::withdraw_offer(Origin::signed(2), kitty),
+			Error::<Test>::OfferNotExist
+		);
+		assert_ok!(KittiesModule::make_offer(Origin::signed(2), kitty, 700, 10));
+
+		assert_ok!(KittiesModule::withdraw_offer(Origin::signed(2), kitty));
+
+		assert!(KittiesModule::offers(kitty, 2).is_none());
+		assert!(!OffersExpiringAt::<Test>::contains_key(10));
+		assert_eq!(Balances::reserved_balance(2), 0);
+		System::assert_last_event(Event::KittiesModule(KittiesEvent::OfferWithdrawn(2, kitty)));
+
+		// 撤回之后可以重新报价
+		assert_ok!(KittiesModule::make_offer(Origin::signed(2), kitty, 800, 10));
+	});
+}
+
+#[test]
+fn expired_offers_are_cleaned_up_on_idle() {
+	new_test_ext().execute_with(|| {
+		let kitty = create_kitty(1);
+		assert_ok!(KittiesModule::make_offer(Origin::signed(2), kitty, 700, 3));
+		assert_ok!(KittiesModule::make_offer(Origin::signed(3), kitty, 800, 6));
+
+		System::set_block_number(5);
+		KittiesModule::on_idle(5, Weight::max_value());
+
+		assert!(KittiesModule::offers(kitty, 2).is_none());
+		assert!(!OffersExpiringAt::<Test>::contains_key(3));
+		assert_eq!(Balances::reserved_balance(2), 0);
+		System::assert_last_event(Event::KittiesModule(KittiesEvent::OfferExpired(2, kitty)));
+		assert_eq!(KittiesModule::offers(kitty, 3).unwrap().amount, 800);
+		assert_eq!(KittiesModule::next_offer_cleanup(), Some(6));
+
+		System::set_block_number(6);
+		KittiesModule::on_idle(6, Weight::max_value());
+
+		assert!(KittiesModule::offers(kitty, 3).is_none());
+		assert_eq!(Balances::reserved_balance(3), 0);
+	});
+}
+
+#[test]
+fn offers_on_burned_kitty_are_refunded() {
+	new_test_ext().execute_with(|| {
+		let kitty = create_kitty(1);
+		assert_ok!(KittiesModule::make_offer(Origin::signed(2), kitty, 700, 21));
+		assert_ok!(KittiesModule::make_offer(Origin::signed(3), kitty, 800, 21));
+		assert_ok!(KittiesModule::burn(Origin::signed(1), kitty));
+
+		// 小猫销毁后不能再接受报价，但报价人可以随时撤回
+		assert_ok!(KittiesModule::withdraw_offer(Origin::signed(2), kitty));
+		assert_eq!(Balances::reserved_balance(2), 0);
+
+		// 没有撤回的报价最迟在 MaxOfferDuration 之后失效
+		System::set_block_number(21);
+		KittiesModule::on_idle(21, Weight::max_value());
+		assert!(KittiesModule::offers(kitty, 3).is_none());
+		assert_eq!(Balances::reserved_balance(3), 0);
+	});
+}
+
+#[test]
+fn offer_cleanup_waits_for_first_offer() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(5);
+		KittiesModule::on_idle(5, Weight::max_value());
+		assert_eq!(KittiesModule::next_offer_cleanup(), None);
+
+		// 清理从第一次报价的区块开始
+		let kitty = create_kitty(1);
+		assert_ok!(KittiesModule::make_offer(Origin::signed(2), kitty, 700, 6));
+		assert_eq!(KittiesModule::next_offer_cleanup(), Some(5));
+		System::set_block_number(6);
+		KittiesModule::on_idle(6, Weight::max_value());
+		assert!(KittiesModule::offers(kitty, 2).is_none());
+		assert_eq!(KittiesModule::next_offer_cleanup(), Some(7));
+	});
+}
+
+#[test]
+fn offer_cleanup_stops_when_weight_runs_out() {
+	new_test_ext().execute_with(|| {
+		let kitty = create_kitty(1);
+		assert_ok!(KittiesModule::make_offer(Origin::signed(2), kitty, 700, 2));
+		assert_ok!(KittiesModule::make_offer(Origin::signed(3), kitty, 800, 3));
+
+		System::set_block_number(5);
+		KittiesModule::on_idle(5, 0);
+		assert_eq!(KittiesModule::offers(kitty, 2).unwrap().amount, 700);
+		assert_eq!(KittiesModule::next_offer_cleanup(), Some(1));
+
+		// 权重只够清理一个区块
+		let limit = <() as WeightInfo>::expire_offers(0) + <() as WeightInfo>::expire_offers(1);
+		KittiesModule::on_idle(5, limit);
+		assert!(KittiesModule::offers(kitty, 2).is_none());
+		assert_eq!(KittiesModule::offers(kitty, 3).unwrap().amount, 800);
+		assert_eq!(KittiesModule::next_offer_cleanup(), Some(3));
+
+		KittiesModule::on_idle(5, Weight::max_value());
+		assert!(KittiesModule::offers(kitty, 3).is_none());
+		assert_eq!(KittiesModule::next_offer_cleanup(), Some(6));
+	});
+}
+
+#[test]
+fn owned_kitties_page_works() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(KittiesModule::create_batch(Origin::signed(1), 5));
+
+		assert_eq!(KittiesModule::owned_kitties_page(&1, 0, 2), vec![1, 2]);
+		assert_eq!(KittiesModule::owned_kitties_page(&1, 2, 2), vec![5]);
+		assert!(KittiesModule::owned_kitties_page(&1, 3, 2).is_empty());
+		assert!(KittiesModule::owned_kitties_page(&2, 0, 2).is_empty());
+	});
+}
+
+#[test]
+fn listed_kitties_page_works() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(KittiesModule::create_batch(Origin::signed(1), 5));
+		for kitty_id in [1, 3, 4] {
+			assert_ok!(KittiesModule::set_price(Origin::signed(1), kitty_id, 500));
+		}
+
+		let mut listed = KittiesModule::listed_kitties_page(0, 2);
+		listed.extend(KittiesModule::listed_kitties_page(1, 2));
+		listed.sort();
+		assert_eq!(listed, vec![1, 3, 4]);
+		assert!(KittiesModule::listed_kitties_page(2, 2).is_empty());
+	});
+}
+
+#[test]
+fn genesis_config_mints_kitties() {
+	let kitties = vec![(1, [1u8; 16], None), (2, [2u8; 16], Some(500))];
+
+	new_test_ext_with_kitties(kitties).execute_with(|| {
+		assert_eq!(KittiesModule::kitties_count(), Some(3));
+		assert_eq!(KittiesModule::owner(1), Some(1));
+		assert_eq!(KittiesModule::owner(2), Some(2));
+		assert_eq!(KittiesModule::kitties(1).unwrap().dna, [1u8; 16]);
+		assert_eq!(KittiesModule::kitties(2).unwrap().price, Some(500));
+		assert_eq!(KittiesModule::owned_kitties(2).into_inner(), vec![2]);
+		assert_eq!(Balances::reserved_balance(2), KittyReserve::get());
+
+		// 创世之后继续按顺序编号
+		assert_eq!(create_kitty(3), 3);
+	});
+}
+
+#[test]
+#[should_panic(expected = "Duplicate dna in genesis kitties")]
+fn genesis_config_rejects_duplicate_dna() {
+	new_test_ext_with_kitties(vec![(1, [1u8; 16], None), (2, [1u8; 16], None)]);
+}
+
+// 用哈希生成一组确定的伪随机DNA
+fn sample_dna(tag: &[u8], i: u32) -> [u8; 16] {
+	(tag, i).using_encoded(sp_io::hashing::blake2_128)
+}
+
+#[test]
+fn bit_select_mixer_takes_every_bit_from_a_parent() {
+	for i in 0..1_000 {
+		let dna_1 = sample_dna(b"dna_1", i);
+		let dna_2 = sample_dna(b"dna_2", i);
+		let selector = sample_dna(b"selector", i);
+
+		let child = BitSelectMixer::mix(&dna_1, &dna_2, &selector);
+
+		// 把 128 位DNA当成一个整数，逐位比较
+		let [child, dna_1, dna_2, selector] =
+			[child, dna_1, dna_2, selector].map(u128::from_le_bytes);
+		// 孩子的每一位都等于父母其中一方的对应位
+		assert_eq!((child ^ dna_1) & (child ^ dna_2), 0);
+		// selector 为 1 的位来自 dna_1，为 0 的位来自 dna_2
+		assert_eq!((child ^ dna_1) & selector, 0);
+		assert_eq!((child ^ dna_2) & !selector, 0);
+	}
+}
+
+#[test]
+fn bit_select_mixer_uses_second_parent_where_selector_is_zero() {
+	let dna_1 = [0x00; 16];
+	let dna_2 = [0xff; 16];
+
+	assert_eq!(BitSelectMixer::mix(&dna_1, &dna_2, &[0x00; 16]), dna_2);
+	assert_eq!(BitSelectMixer::mix(&dna_1, &dna_2, &[0xff; 16]), dna_1);
+	assert_eq!(BitSelectMixer::mix(&dna_1, &dna_2, &[0x0f; 16]), [0xf0; 16]);
+}
+
+// 这些结果是布局 V1 的一部分，改变它们会改变已有小猫的外观
+#[test]
+fn phenotype_v1_decoding_is_stable() {
+	let dna = [0x00, 0xff, 0x40, 0xc0, 0x5f, 0x20, 0xa0, 0xfe, 9, 9, 9, 9, 9, 9, 9, 9];
+	let phenotype = decode_phenotype(&dna);
+	assert_eq!(
+		phenotype,
+		Phenotype {
+			layout: GeneLayout::V1,
+			body_color: BodyColor::Black,
+			eye_shape: EyeShape::Almond,
+			pattern: Pattern::Tabby,
+			rarity: Rarity::Uncommon,
+			hidden: RecessiveGenes {
+				body_color: BodyColor::Fawn,
+				eye_shape: EyeShape::Slanted,
+				pattern: Pattern::Spotted,
+				rarity: Rarity::Legendary,
+			},
+		}
+	);
+	assert_eq!(phenotype.rarity_score(), 12);
+
+	let lowest = decode_phenotype(&[0x00; 16]);
+	assert_eq!(
+		(lowest.body_color, lowest.eye_shape, lowest.pattern, lowest.rarity),
+		(BodyColor::Black, EyeShape::Round, Pattern::Solid, Rarity::Common)
+	);
+	assert_eq!(lowest.rarity_score(), 0);
+
+	let highest = decode_phenotype(&[0xff; 16]);
+	assert_eq!(
+		(highest.body_color, highest.eye_shape, highest.pattern, highest.rarity),
+		(BodyColor::Fawn, EyeShape::Slanted, Pattern::Marbled, Rarity::Legendary)
+	);
+	assert_eq!(highest.rarity_score(), 57);
+}
+
+#[test]
+fn rarity_alleles_have_fixed_ranges() {
+	let tiers = [
+		(0, Rarity::Common),
+		(159, Rarity::Common),
+		(160, Rarity::Uncommon),
+		(219, Rarity::Uncommon),
+		(220, Rarity::Rare),
+		(245, Rarity::Rare),
+		(246, Rarity::Epic),
+		(253, Rarity::Epic),
+		(254, Rarity::Legendary),
+		(255, Rarity::Legendary),
+	];
+	for (allele, rarity) in tiers {
+		assert_eq!(Rarity::from_allele(allele), rarity);
+	}
+}
+
+#[test]
+fn phenotype_ignores_allele_order_and_reserved_bytes() {
+	for i in 0..1_000 {
+		let dna = sample_dna(b"phenotype", i);
+		let phenotype = decode_phenotype(&dna);
+
+		let mut swapped = dna;
+		for &gene in GeneLayout::CURRENT.genes() {
+			swapped.swap(gene, gene + 1);
+		}
+		assert_eq!(decode_phenotype(&swapped), phenotype);
+
+		let mut reserved = dna;
+		reserved[8..].copy_from_slice(&sample_dna(b"reserved", i)[8..]);
+		assert_eq!(decode_phenotype(&reserved), phenotype);
+
+		// 表现出的性状总是两个等位基因中更显性的那个
+		assert!(phenotype.body_color <= phenotype.hidden.body_color);
+		assert!(phenotype.eye_shape <= phenotype.hidden.eye_shape);
+		assert!(phenotype.pattern <= phenotype.hidden.pattern);
+		assert!(phenotype.rarity <= phenotype.hidden.rarity);
+	}
+}
+
+parameter_types! {
+	pub const NoMutation: Perbill = Perbill::from_percent(0);
+	pub const SomeMutation: Perbill = Perbill::from_percent(10);
+	pub const AlwaysMutate: Perbill = Perbill::from_percent(100);
+}
+
+#[test]
+fn mendelian_mixer_takes_one_allele_from_each_parent() {
+	let genes = GeneLayout::CURRENT.genes();
+	for i in 0..1_000 {
+		let dna_1 = sample_dna(b"dna_1", i);
+		let dna_2 = sample_dna(b"dna_2", i);
+		let selector = sample_dna(b"selector", i);
+
+		let child = MendelianMixer::<NoMutation>::mix(&dna_1, &dna_2, &selector);
+
+		for &gene in genes {
+			assert!(child[gene] == dna_1[gene] || child[gene] == dna_1[gene + 1]);
+			assert!(child[gene + 1] == dna_2[gene] || child[gene + 1] == dna_2[gene + 1]);
+		}
+
+		// 保留字节按位混合
+		let bit_select = BitSelectMixer::mix(&dna_1, &dna_2, &selector);
+		for (byte, (mixed, selected)) in child.iter().zip(bit_select).enumerate() {
+			if !genes.iter().any(|&gene| byte == gene || byte == gene + 1) {
+				assert_eq!(*mixed, selected);
+			}
+		}
+	}
+}
+
+#[test]
+fn mendelian_mixer_follows_dominance_ratios() {
+	// 父母都是 黑色(显性) + 浅黄褐色(隐性) 的杂合子，孩子表现为浅黄褐色的概率是 1/4
+	let mut parent = [0u8; 16];
+	parent[1] = 0xff;
+
+	let fawn = (0..1_000)
+		.map(|i| MendelianMixer::<NoMutation>::mix(&parent, &parent, &sample_dna(b"selector", i)))
+		.filter(|child| decode_phenotype(child).body_color == BodyColor::Fawn)
+		.count();
+	assert!((200..300).contains(&fawn), "{} of 1000 children are fawn", fawn);
+}
+
+#[test]
+fn mendelian_mixer_mutates_at_the_configured_rate() {
+	let parent = [0u8; 16];
+	let mutated = |child: [u8; 16]| {
+		GeneLayout::CURRENT
+			.genes()
+			.iter()
+			.map(|&gene| (child[gene] != 0) as u32 + (child[gene + 1] != 0) as u32)
+			.sum::<u32>()
+	};
+
+	let (mut some, mut all) = (0, 0);
+	for i in 0..1_000 {
+		let selector = sample_dna(b"selector", i);
+		assert_eq!(MendelianMixer::<NoMutation>::mix(&parent, &parent, &selector), parent);
+
+		let child = MendelianMixer::<SomeMutation>::mix(&parent, &parent, &selector);
+		// 保留字节不会突变
+		assert_eq!(child[8..], parent[8..]);
+		some += mutated(child);
+		all += mutated(MendelianMixer::<AlwaysMutate>::mix(&parent, &parent, &selector));
+	}
+
+	// 8000 个等位基因。突变成 0 的等位基因看不出来，概率是 1/256
+	assert!((700..900).contains(&some), "{} of 8000 alleles mutated", some);
+	assert!(all > 7_900, "{} of 8000 alleles mutated", all);
+}
+
+#[test]
+fn phenotype_decodes_stored_kitty() {
+	new_test_ext().execute_with(|| {
+		let kitty_id = create_kitty(1);
+		let dna = Kitties::<Test>::get(kitty_id).unwrap().dna;
+
+		assert_eq!(KittiesModule::phenotype(kitty_id), Some(decode_phenotype(&dna)));
+		assert_eq!(KittiesModule::phenotype(kitty_id + 1), None);
+	});
+}
+
+#[test]
+fn migrate_to_v1_adds_lineage_to_existing_kitties() {
+	new_test_ext().execute_with(|| {
+		StorageVersion::new(0).put::<KittiesModule>();
+		let old = migrations::v1::OldKitty { dna: [7u8; 16], price: Some(500u64) };
+		unhashed::put(&Kitties::<Test>::hashed_key_for(1), &old);
+
+		migrations::v1::migrate::<Test>();
+
+		let kitty: migrations::v2::OldKitty<u64, u64> =
+			unhashed::get(&Kitties::<Test>::hashed_key_for(1)).unwrap();
+		assert_eq!(kitty.dna, [7u8; 16]);
+		assert_eq!(kitty.price, Some(500));
+		assert_eq!(kitty.parents, None);
+		assert_eq!(kitty.generation, 0);
+		assert_eq!(kitty.birth_block, 0);
+		assert_eq!(KittiesModule::on_chain_storage_version(), 1);
+	});
+}
+
+#[test]
+fn migrate_to_v2_adds_unknown_creator() {
+	new_test_ext().execute_with(|| {
+		StorageVersion::new(1).put::<KittiesModule>();
+		let old = migrations::v2::OldKitty {
+			dna: [7u8; 16],
+			price: Some(500u64),
+			parents: Some((1, 2)),
+			generation: 3,
+			birth_block: 42u64,
+		};
+		unhashed::put(&Kitties::<Test>::hashed_key_for(3), &old);
+
+		migrations::v2::migrate::<Test>();
+
+		let kitty = KittiesModule::kitties(3).unwrap();
+		assert_eq!(kitty.dna, [7u8; 16]);
+		assert_eq!(kitty.price, Some(500));
+		assert_eq!(kitty.parents, Some((1, 2)));
+		assert_eq!(kitty.generation, 3);
+		assert_eq!(kitty.birth_block, 42);
+		assert_eq!(kitty.creator, None);
+		assert_eq!(KittiesModule::on_chain_storage_version(), 2);
+	});
+}
+
+#[test]
+fn migrate_to_v3_fills_owned_kitties() {
+	new_test_ext().execute_with(|| {
+		StorageVersion::new(2).put::<KittiesModule>();
+		Owner::<Test>::insert(1, 1);
+		Owner::<Test>::insert(2, 1);
+		Owner::<Test>::insert(3, 2);
+		// 升级后铸造的小猫已经在列表中，不会重复加入
+		OwnedKitties::<Test>::insert(1, BoundedVec::try_from(vec![2]).unwrap());
+
+		migrations::v3::migrate::<Test>();
+
+		let mut owned = KittiesModule::owned_kitties(1).into_inner();
+		owned.sort();
+		assert_eq!(owned, vec![1, 2]);
+		assert_eq!(KittiesModule::owned_kitties(2).into_inner(), vec![3]);
+		assert_eq!(KittiesModule::on_chain_storage_version(), 3);
+	});
+}
+
+#[test]
+fn migrate_to_v1_is_noop_when_already_migrated() {
+	new_test_ext().execute_with(|| {
+		StorageVersion::new(1).put::<KittiesModule>();
+		let old = migrations::v1::OldKitty { dna: [7u8; 16], price: Some(500u64) };
+		unhashed::put(&Kitties::<Test>::hashed_key_for(1), &old);
+
+		migrations::v1::migrate::<Test>();
+
+		assert_eq!(unhashed::get_raw(&Kitties::<Test>::hashed_key_for(1)), Some(old.encode()));
+		assert_eq!(KittiesModule::on_chain_storage_version(), 1);
+	});
+}
+
+#[test]
+fn migrate_runs_pending_migrations_in_order() {
+	new_test_ext().execute_with(|| {
+		StorageVersion::new(0).put::<KittiesModule>();
+		let old = migrations::v1::OldKitty { dna: [7u8; 16], price: None::<u64> };
+		unhashed::put(&Kitties::<Test>::hashed_key_for(1), &old);
+
+		migrations::migrate::<Test>();
+
+		let kitty = KittiesModule::kitties(1).unwrap();
+		assert_eq!(kitty.generation, 0);
+		assert_eq!(kitty.creator, None);
+		assert_eq!(KittiesModule::on_chain_storage_version(), 3);
+	});
+}
+
+#[cfg(feature = "try-runtime")]
+#[test]
+fn migrate_to_v1_passes_try_runtime_checks() {
+	new_test_ext().execute_with(|| {
+		StorageVersion::new(0).put::<KittiesModule>();
+		for kitty_id in 1..=3 {
+			let old = migrations::v1::OldKitty { dna: [kitty_id as u8; 16], price: None::<u64> };
+			unhashed::put(&Kitties::<Test>::hashed_key_for(kitty_id), &old);
+		}
+
+		assert_ok!(migrations::pre_upgrade::<Test>());
+		migrations::migrate::<Test>();
+		assert_ok!(migrations::post_upgrade::<Test>());
+	});
+}
+
+#[cfg(feature = "try-runtime")]
+#[test]
+fn post_upgrade_fails_if_version_not_bumped() {
+	new_test_ext().execute_with(|| {
+		StorageVersion::new(0).put::<KittiesModule>();
+
+		assert!(migrations::post_upgrade::<Test>().is_err());
+	});
+}
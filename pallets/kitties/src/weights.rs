@@ -0,0 +1,344 @@
+//! Placeholder weights for pallet_kitties
+//!
+//! This file was NOT generated by the benchmark CLI. Each call costs the flat `10_000` used by
+//! pallet-template plus the database reads and writes listed in its `Storage:` comments, which
+//! are counted from the code. Replace it with the output of
+//!
+//! ./target/release/node-template benchmark --chain=dev --steps=50 --repeat=20
+//! --pallet=pallet_kitties --extrinsic=* --execution=wasm --wasm-execution=compiled
+//! --heap-pages=4096 --output=./pallets/kitties/src/weights.rs
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{traits::Get, weights::{Weight, constants::RocksDbWeight}};
+use sp_std::marker::PhantomData;
+
+/// Weight functions needed for pallet_kitties.
+pub trait WeightInfo {
+	fn create() -> Weight;
+	fn breed() -> Weight;
+	fn set_price() -> Weight;
+	fn unlist() -> Weight;
+	fn buy_kitty() -> Weight;
+	fn transfer() -> Weight;
+	fn offer_sire() -> Weight;
+	fn cancel_sire_offer() -> Weight;
+	fn burn() -> Weight;
+	fn create_auction() -> Weight;
+	fn bid() -> Weight;
+	fn on_initialize(n: u32) -> Weight;
+	fn make_offer() -> Weight;
+	fn accept_offer() -> Weight;
+	fn withdraw_offer() -> Weight;
+	fn expire_offers(n: u32) -> Weight;
+	fn create_batch(n: u32) -> Weight;
+	fn transfer_batch(n: u32) -> Weight;
+}
+
+/// Placeholder weights for pallet_kitties, see the module docs.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	// Storage: RandomnessCollectiveFlip RandomMaterial (r:1 w:0)
+	// Storage: KittyModule KittiesCount (r:1 w:1)
+	// Storage: KittyModule OwnedKitties (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	// Storage: KittyModule Kitties (r:0 w:1)
+	// Storage: KittyModule Owner (r:0 w:1)
+	// Storage: KittyModule KittyDeposits (r:0 w:1)
+	fn create() -> Weight {
+		(10_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(4 as Weight))
+			.saturating_add(T::DbWeight::get().writes(6 as Weight))
+	}
+	// Storage: KittyModule Kitties (r:2 w:1)
+	// Storage: KittyModule Auctions (r:2 w:0)
+	// Storage: KittyModule NextBreedableAt (r:2 w:2)
+	// Storage: KittyModule Owner (r:2 w:1)
+	// Storage: KittyModule SireOffers (r:1 w:0)
+	// Storage: System Account (r:2 w:2)
+	// Storage: RandomnessCollectiveFlip RandomMaterial (r:1 w:0)
+	// Storage: KittyModule KittiesCount (r:1 w:1)
+	// Storage: KittyModule OwnedKitties (r:1 w:1)
+	// Storage: KittyModule KittyDeposits (r:0 w:1)
+	fn breed() -> Weight {
+		(10_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(14 as Weight))
+			.saturating_add(T::DbWeight::get().writes(9 as Weight))
+	}
+	// Storage: KittyModule Kitties (r:1 w:1)
+	// Storage: KittyModule Owner (r:1 w:0)
+	// Storage: KittyModule Auctions (r:1 w:0)
+	fn set_price() -> Weight {
+		(10_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	// Storage: KittyModule Kitties (r:1 w:1)
+	// Storage: KittyModule Owner (r:1 w:0)
+	fn unlist() -> Weight {
+		(10_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	// Storage: KittyModule Kitties (r:1 w:1)
+	// Storage: KittyModule Owner (r:1 w:1)
+	// Storage: System Account (r:3 w:3)
+	// Storage: KittyModule OwnedKitties (r:2 w:2)
+	// Storage: KittyModule SireOffers (r:0 w:1)
+	// Storage: KittyModule KittyDeposits (r:1 w:1)
+	fn buy_kitty() -> Weight {
+		(10_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(8 as Weight))
+			.saturating_add(T::DbWeight::get().writes(9 as Weight))
+	}
+	// Storage: KittyModule Kitties (r:1 w:1)
+	// Storage: KittyModule Owner (r:1 w:1)
+	// Storage: KittyModule Auctions (r:1 w:0)
+	// Storage: KittyModule OwnedKitties (r:2 w:2)
+	// Storage: System Account (r:2 w:2)
+	// Storage: KittyModule SireOffers (r:0 w:1)
+	// Storage: KittyModule KittyDeposits (r:1 w:1)
+	fn transfer() -> Weight {
+		(10_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(8 as Weight))
+			.saturating_add(T::DbWeight::get().writes(8 as Weight))
+	}
+	// Storage: KittyModule Owner (r:1 w:0)
+	// Storage: KittyModule SireOffers (r:0 w:1)
+	fn offer_sire() -> Weight {
+		(10_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	// Storage: KittyModule Owner (r:1 w:0)
+	// Storage: KittyModule SireOffers (r:1 w:1)
+	fn cancel_sire_offer() -> Weight {
+		(10_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	// Storage: KittyModule Owner (r:1 w:1)
+	// Storage: KittyModule Auctions (r:1 w:0)
+	// Storage: KittyModule OwnedKitties (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	// Storage: KittyModule Kitties (r:0 w:1)
+	// Storage: KittyModule SireOffers (r:0 w:1)
+	// Storage: KittyModule NextBreedableAt (r:0 w:1)
+	// Storage: KittyModule KittyDeposits (r:1 w:1)
+	fn burn() -> Weight {
+		(10_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(5 as Weight))
+			.saturating_add(T::DbWeight::get().writes(7 as Weight))
+	}
+	// Storage: KittyModule Kitties (r:1 w:1)
+	// Storage: KittyModule Owner (r:1 w:0)
+	// Storage: KittyModule Auctions (r:1 w:1)
+	// Storage: KittyModule AuctionsEndingAt (r:1 w:1)
+	fn create_auction() -> Weight {
+		(10_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(4 as Weight))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+	}
+	// Storage: KittyModule Auctions (r:1 w:1)
+	// Storage: System Account (r:2 w:2)
+	fn bid() -> Weight {
+		(10_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+	}
+	// Storage: KittyModule AuctionsEndingAt (r:1 w:1)
+	// Storage: KittyModule Auctions (r:1 w:1)
+	// Storage: KittyModule Kitties (r:1 w:0)
+	// Storage: System Account (r:3 w:3)
+	// Storage: KittyModule OwnedKitties (r:2 w:2)
+	// Storage: KittyModule Owner (r:0 w:1)
+	// Storage: KittyModule SireOffers (r:0 w:1)
+	// Storage: KittyModule KittyDeposits (r:1 w:1)
+	fn on_initialize(n: u32) -> Weight {
+		(10_000 as Weight)
+			.saturating_add((10_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().reads((10 as Weight).saturating_mul(n as Weight)))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes((10 as Weight).saturating_mul(n as Weight)))
+	}
+	// Storage: KittyModule Owner (r:1 w:0)
+	// Storage: KittyModule Offers (r:1 w:1)
+	// Storage: KittyModule OffersExpiringAt (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	// Storage: KittyModule NextOfferCleanup (r:1 w:1)
+	fn make_offer() -> Weight {
+		(10_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(5 as Weight))
+			.saturating_add(T::DbWeight::get().writes(4 as Weight))
+	}
+	// Storage: KittyModule Kitties (r:1 w:1)
+	// Storage: KittyModule Owner (r:1 w:1)
+	// Storage: KittyModule Auctions (r:1 w:0)
+	// Storage: KittyModule Offers (r:1 w:1)
+	// Storage: KittyModule OffersExpiringAt (r:1 w:1)
+	// Storage: System Account (r:3 w:3)
+	// Storage: KittyModule OwnedKitties (r:2 w:2)
+	// Storage: KittyModule SireOffers (r:0 w:1)
+	// Storage: KittyModule KittyDeposits (r:1 w:1)
+	fn accept_offer() -> Weight {
+		(10_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(11 as Weight))
+			.saturating_add(T::DbWeight::get().writes(11 as Weight))
+	}
+	// Storage: KittyModule Offers (r:1 w:1)
+	// Storage: KittyModule OffersExpiringAt (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	fn withdraw_offer() -> Weight {
+		(10_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+	}
+	// Storage: KittyModule OffersExpiringAt (r:1 w:1)
+	// Storage: KittyModule Offers (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	fn expire_offers(n: u32) -> Weight {
+		(10_000 as Weight)
+			.saturating_add((10_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().reads((2 as Weight).saturating_mul(n as Weight)))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes((2 as Weight).saturating_mul(n as Weight)))
+	}
+	// Storage: RandomnessCollectiveFlip RandomMaterial (r:1 w:0)
+	// Storage: KittyModule KittiesCount (r:1 w:1)
+	// Storage: KittyModule OwnedKitties (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	// Storage: KittyModule Kitties (r:0 w:1)
+	// Storage: KittyModule Owner (r:0 w:1)
+	// Storage: KittyModule KittyDeposits (r:0 w:1)
+	fn create_batch(n: u32) -> Weight {
+		(10_000 as Weight)
+			.saturating_add((10_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes((3 as Weight).saturating_mul(n as Weight)))
+	}
+	// Storage: KittyModule OwnedKitties (r:1 w:1)
+	// Storage: KittyModule Kitties (r:1 w:1)
+	// Storage: KittyModule Owner (r:1 w:1)
+	// Storage: KittyModule Auctions (r:1 w:0)
+	// Storage: System Account (r:2 w:2)
+	// Storage: KittyModule SireOffers (r:0 w:1)
+	// Storage: KittyModule KittyDeposits (r:1 w:1)
+	fn transfer_batch(n: u32) -> Weight {
+		(10_000 as Weight)
+			.saturating_add((10_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().reads((7 as Weight).saturating_mul(n as Weight)))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes((7 as Weight).saturating_mul(n as Weight)))
+	}
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+	fn create() -> Weight {
+		(10_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(4 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(6 as Weight))
+	}
+	fn breed() -> Weight {
+		(10_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(14 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(9 as Weight))
+	}
+	fn set_price() -> Weight {
+		(10_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	fn unlist() -> Weight {
+		(10_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	fn buy_kitty() -> Weight {
+		(10_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(8 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(9 as Weight))
+	}
+	fn transfer() -> Weight {
+		(10_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(8 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(8 as Weight))
+	}
+	fn offer_sire() -> Weight {
+		(10_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	fn cancel_sire_offer() -> Weight {
+		(10_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	fn burn() -> Weight {
+		(10_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(5 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(7 as Weight))
+	}
+	fn create_auction() -> Weight {
+		(10_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(4 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
+	}
+	fn bid() -> Weight {
+		(10_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
+	}
+	fn on_initialize(n: u32) -> Weight {
+		(10_000 as Weight)
+			.saturating_add((10_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().reads((10 as Weight).saturating_mul(n as Weight)))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes((10 as Weight).saturating_mul(n as Weight)))
+	}
+	fn make_offer() -> Weight {
+		(10_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(5 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(4 as Weight))
+	}
+	fn accept_offer() -> Weight {
+		(10_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(11 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(11 as Weight))
+	}
+	fn withdraw_offer() -> Weight {
+		(10_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
+	}
+	fn expire_offers(n: u32) -> Weight {
+		(10_000 as Weight)
+			.saturating_add((10_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().reads((2 as Weight).saturating_mul(n as Weight)))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes((2 as Weight).saturating_mul(n as Weight)))
+	}
+	fn create_batch(n: u32) -> Weight {
+		(10_000 as Weight)
+			.saturating_add((10_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
+			.saturating_add(RocksDbWeight::get().writes((3 as Weight).saturating_mul(n as Weight)))
+	}
+	fn transfer_batch(n: u32) -> Weight {
+		(10_000 as Weight)
+			.saturating_add((10_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().reads((7 as Weight).saturating_mul(n as Weight)))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes((7 as Weight).saturating_mul(n as Weight)))
+	}
+}
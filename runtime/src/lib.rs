@@ -99,10 +99,10 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
 	//   `spec_version`, and `authoring_version` are the same between Wasm and native.
 	// This value is set to 100 to notify Polkadot-JS App (https://polkadot.js.org/apps) to use
 	//   the compatible custom types.
-	spec_version: 100,
+	spec_version: 101,
 	impl_version: 1,
 	apis: RUNTIME_API_VERSIONS,
-	transaction_version: 1,
+	transaction_version: 2,
 };
 
 /// This determines the average expected block time that we are targeting.
@@ -280,10 +280,38 @@ impl pallet_template::Config for Runtime {
 	type Event = Event;
 }
 
+parameter_types! {
+	pub const MaxKittiesOwned: u32 = 100;
+	pub const KittyReserve: Balance = 10_000;
+	pub const BreedCooldown: BlockNumber = 10 * MINUTES;
+	pub const MaxAuctionsEndingPerBlock: u32 = 50;
+	pub const MaxAuctionDuration: BlockNumber = 7 * DAYS;
+	pub const MaxOffersExpiringPerBlock: u32 = 100;
+	pub const MaxOfferDuration: BlockNumber = 7 * DAYS;
+	pub const MaxBatchSize: u32 = 20;
+	pub const MarketplaceFee: Perbill = Perbill::from_percent(2);
+	pub const CreatorRoyalty: Perbill = Perbill::from_percent(5);
+	pub const MutationRate: Perbill = Perbill::from_percent(1);
+}
+
 impl pallet_kitties::Config for Runtime {
 	type Event = Event;
 	type Currency = Balances;
 	type Randomness = RandomnessCollectiveFlip;
+	type DnaMixer = pallet_kitties::dna::MendelianMixer<MutationRate>;
+	type MaxKittiesOwned = MaxKittiesOwned;
+	type KittyReserve = KittyReserve;
+	type BreedCooldown = BreedCooldown;
+	type MaxAuctionsEndingPerBlock = MaxAuctionsEndingPerBlock;
+	type MaxAuctionDuration = MaxAuctionDuration;
+	type MaxOffersExpiringPerBlock = MaxOffersExpiringPerBlock;
+	type MaxOfferDuration = MaxOfferDuration;
+	type MarketplaceFee = MarketplaceFee;
+	// There is no treasury in this runtime, so marketplace fees are burned.
+	type OnMarketplaceFee = ();
+	type CreatorRoyalty = CreatorRoyalty;
+	type MaxBatchSize = MaxBatchSize;
+	type WeightInfo = pallet_kitties::weights::SubstrateWeight<Runtime>;
 }
 
 // Create the runtime by composing the FRAME pallets that were previously configured.
@@ -334,6 +362,24 @@ pub type Executive = frame_executive::Executive<
 	AllPalletsWithSystem,
 >;
 
+/// Collects everything the kitties RPC exposes about a single kitty.
+fn kitty_info(
+	kitty_id: pallet_kitties::KittyIndex,
+) -> Option<pallet_kitties_rpc_runtime_api::KittyInfo<AccountId, Balance, BlockNumber>> {
+	let kitty = KittyModule::kitties(kitty_id)?;
+	let owner = KittyModule::owner(kitty_id)?;
+	Some(pallet_kitties_rpc_runtime_api::KittyInfo {
+		id: kitty_id,
+		owner,
+		dna: kitty.dna,
+		price: kitty.price,
+		parents: kitty.parents,
+		generation: kitty.generation,
+		birth_block: kitty.birth_block,
+		creator: kitty.creator,
+	})
+}
+
 impl_runtime_apis! {
 	impl sp_api::Core<Block> for Runtime {
 		fn version() -> RuntimeVersion {
@@ -465,6 +511,38 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl pallet_kitties_rpc_runtime_api::KittiesApi<Block, AccountId, Balance, BlockNumber> for Runtime {
+		fn kitty(
+			kitty_id: u32,
+		) -> Option<pallet_kitties_rpc_runtime_api::KittyInfo<AccountId, Balance, BlockNumber>> {
+			kitty_info(kitty_id)
+		}
+
+		fn kitties_by_owner(
+			owner: AccountId,
+			page: u32,
+		) -> Vec<pallet_kitties_rpc_runtime_api::KittyInfo<AccountId, Balance, BlockNumber>> {
+			KittyModule::owned_kitties_page(&owner, page, pallet_kitties_rpc_runtime_api::PAGE_SIZE)
+				.into_iter()
+				.filter_map(kitty_info)
+				.collect()
+		}
+
+		fn listed_kitties(
+			page: u32,
+		) -> Vec<pallet_kitties_rpc_runtime_api::KittyInfo<AccountId, Balance, BlockNumber>> {
+			KittyModule::listed_kitties_page(page, pallet_kitties_rpc_runtime_api::PAGE_SIZE)
+				.into_iter()
+				.filter_map(kitty_info)
+				.collect()
+		}
+
+		fn kitties_count() -> u32 {
+			// `KittiesCount` holds the next index to assign, and indices start at 1.
+			KittyModule::kitties_count().unwrap_or(1).saturating_sub(1)
+		}
+	}
+
 	#[cfg(feature = "runtime-benchmarks")]
 	impl frame_benchmarking::Benchmark<Block> for Runtime {
 		fn benchmark_metadata(extra: bool) -> (
@@ -483,6 +561,7 @@ impl_runtime_apis! {
 			list_benchmark!(list, extra, pallet_balances, Balances);
 			list_benchmark!(list, extra, pallet_timestamp, Timestamp);
 			list_benchmark!(list, extra, pallet_template, TemplateModule);
+			list_benchmark!(list, extra, pallet_kitties, KittyModule);
 
 			let storage_info = AllPalletsWithSystem::storage_info();
 
@@ -521,8 +600,24 @@ impl_runtime_apis! {
 			add_benchmark!(params, batches, pallet_balances, Balances);
 			add_benchmark!(params, batches, pallet_timestamp, Timestamp);
 			add_benchmark!(params, batches, pallet_template, TemplateModule);
+			add_benchmark!(params, batches, pallet_kitties, KittyModule);
 
 			Ok(batches)
 		}
 	}
+
+	#[cfg(feature = "try-runtime")]
+	impl frame_try_runtime::TryRuntime<Block> for Runtime {
+		fn on_runtime_upgrade() -> (Weight, Weight) {
+			// NOTE: intentional unwrap: we don't want to propagate the error backwards, and want to
+			// have a backtrace here. If any of the pre/post migration checks fail, we shall stop
+			// right here and right now.
+			let weight = Executive::try_runtime_upgrade().unwrap();
+			(weight, BlockWeights::get().max_block)
+		}
+
+		fn execute_block_no_check(block: Block) -> Weight {
+			Executive::execute_block_no_check(block)
+		}
+	}
 }